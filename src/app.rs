@@ -1,9 +1,20 @@
 use std::iter::zip;
 
-use yew::{Component, html};
+use yew::{Component, Html, html};
 
 use crate::{
-    constraint_input::ConstraintInput, simplex::Simplex, target_input::TargetInput,
+    bound_input::BoundInput,
+    branch_and_bound::{BranchAndBound, BranchStatus},
+    constraint_input::ConstraintInput,
+    gomory::{CuttingPlanes, GomoryError},
+    optimal_face::OptimalFace,
+    rational::Rational,
+    scalar::Scalar,
+    simplex::{ObjectiveSense, Relation, Simplex, SolveResult, StartMethod, VariableKind},
+    stepper::Stepper,
+    tableau_view::TableauView,
+    target_input::TargetInput,
+    trace::Iteration,
     variable_input::VariableInput,
 };
 
@@ -12,25 +23,460 @@ pub struct App {
     current_variable: String,
     target_inputs: Vec<String>,
     constraint_inputs: Vec<String>,
-    simplex: Simplex,
-    solution: Option<f32>,
-    final_values: Vec<(String, f32)>,
+    constraint_relation: Relation,
+    /// Always solved over [`Rational`]: the page teaches the method, so its
+    /// tableaux show exact fractions like `7/3` rather than rounded floats,
+    /// and problems typed by hand are small enough for exact arithmetic to
+    /// stay fast. `f32` and `f64` are for callers of the library.
+    simplex: Simplex<Rational>,
+    result: Option<SolveResult<Rational>>,
+    optimal_face: Option<OptimalFace<Rational>>,
+    /// Whether the steps panel is open, and so whether solves are traced.
+    show_steps: bool,
+    /// Every tableau of the last solve, recorded only while the steps panel
+    /// is open.
+    trace: Vec<Iteration<Rational>>,
+    /// The problem being solved one pivot at a time, if started.
+    stepper: Option<Stepper<Rational>>,
+    /// Whether the pivots of the stepper are chosen by clicking its tableau.
+    manual_pivots: bool,
+    /// Verdict on the last pivot chosen by hand.
+    pivot_feedback: Option<String>,
+    integer_result: Option<BranchAndBound<Rational>>,
+    cutting_planes: Option<Result<CuttingPlanes<Rational>, GomoryError>>,
+    show_dual: bool,
+    dual_result: Option<SolveResult<Rational>>,
+    knapsack_weights: String,
+    knapsack_values: String,
+    knapsack_capacity: String,
     theme: String,
 }
 
 pub enum Msg {
     VariableChange(String),
+    LowerBoundChange((String, usize)),
+    UpperBoundChange((String, usize)),
+    ChangeVariableKind(usize),
     ConstraintChange((String, usize)),
+    RelationChange,
     TargetChange((String, usize)),
     AddVariable,
     ClearVariables,
     AddConstraint,
     ClearConstraints,
     RunSimplex,
+    RunGomory,
+    ChangeObjectiveSense,
+    ChangeAlgorithm,
+    ChangeStartMethod,
+    ChangePricingRule,
+    ChangeAntiCycling,
+    ChangeNodeSelection,
+    ChangeBranchingRule,
+    ToggleDual,
+    ToggleSteps,
+    KnapsackWeightsChange(String),
+    KnapsackValuesChange(String),
+    KnapsackCapacityChange(String),
+    LoadKnapsack,
+    StartStepper,
+    NextStep,
+    PreviousStep,
+    ToggleManualPivots,
+    ChoosePivot((usize, usize)),
     ChangeTheme,
 }
 
-impl App {}
+/// Optimal vertices listed when the optimum is not unique.
+const MAX_OPTIMAL_VERTICES: usize = 20;
+
+/// Cuts the "Gomory cuts" button adds before giving up.
+const MAX_GOMORY_CUTS: usize = 50;
+
+/// Parses a list of numbers separated by commas or spaces.
+fn parse_list(text: &str) -> Option<Vec<Rational>> {
+    text.split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|item| !item.is_empty())
+        .map(|item| item.parse().ok())
+        .collect()
+}
+
+impl App {
+    /// Replaces the problem with the knapsack described by the inputs,
+    /// keeping the solver settings. Returns whether the inputs were valid.
+    fn load_knapsack(&mut self) -> bool {
+        let (Some(weights), Some(values), Ok(capacity)) = (
+            parse_list(&self.knapsack_weights),
+            parse_list(&self.knapsack_values),
+            self.knapsack_capacity.trim().parse::<Rational>(),
+        ) else {
+            return false;
+        };
+        if weights.is_empty() || weights.len() != values.len() {
+            return false;
+        }
+
+        let mut knapsack = Simplex::knapsack(&weights, &values, capacity);
+        knapsack.set_algorithm(self.simplex.get_algorithm());
+        knapsack.set_start_method(self.simplex.get_start_method());
+        knapsack.set_pricing_rule(self.simplex.get_pricing_rule());
+        knapsack.set_anti_cycling(self.simplex.get_anti_cycling());
+        knapsack.set_node_selection(self.simplex.get_node_selection());
+        knapsack.set_branching_rule(self.simplex.get_branching_rule());
+        self.simplex = knapsack;
+
+        self.target_inputs = values.iter().map(|v| v.to_string()).collect();
+        self.target_inputs.push("".to_string());
+        self.constraint_inputs = vec!["".to_string(); weights.len() + 1];
+        self.result = None;
+        self.optimal_face = None;
+        self.trace = vec![];
+        self.stepper = None;
+        self.integer_result = None;
+        self.cutting_planes = None;
+        self.dual_result = None;
+        true
+    }
+
+    /// Sets the target of the problem from the inputs, unless one of them is
+    /// not a number.
+    fn read_target(&mut self) {
+        let mut target = vec![Rational::zero(); self.target_inputs.len()];
+        for (i, input) in self.target_inputs.iter().enumerate() {
+            if input.is_empty() {
+                continue;
+            }
+            match input.parse::<Rational>() {
+                Ok(v) => target[i] = v,
+                Err(_) => return,
+            }
+        }
+        self.simplex.set_target(target);
+    }
+
+    /// Every Gomory cut added, with the row it comes from, and the solution
+    /// once they are in place.
+    fn view_cutting_planes(&self) -> Html {
+        match &self.cutting_planes {
+            None => html! {
+                {"Add cuts to find the integer solution"}
+            },
+            Some(Err(error)) => html! {
+                {format!("Gomory cuts need a pure integer problem: {}", error)}
+            },
+            Some(Ok(cutting_planes)) => html! {
+                <>
+                <ol>
+                    {
+                        for cutting_planes.cuts.iter().map(|cut| html! {
+                            <li>
+                                { for cut.to_string().lines().map(|line| html! { <div>{line.to_string()}</div> }) }
+                            </li>
+                        })
+                    }
+                </ol>
+                { Self::view_result(&Some(cutting_planes.result.clone()), self.simplex.get_objective_sense(), "Z") }
+                </>
+            },
+        }
+    }
+    /// Sensitivity report laid out like the one of the Excel Solver, with the
+    /// allowable ranges of every coefficient and right-hand side.
+    fn view_sensitivity(&self) -> Html {
+        let Some(SolveResult::Optimal {
+            values,
+            sensitivity,
+            ..
+        }) = &self.result
+        else {
+            return html! {
+                {"Available once an optimal solution is found"}
+            };
+        };
+        let limit = |limit: &Option<Rational>| match limit {
+            Some(limit) => limit.to_string(),
+            None => "∞".to_string(),
+        };
+
+        html! {
+            <>
+            <table>
+                <tr>
+                    <th>{"Variable"}</th>
+                    <th>{"Final value"}</th>
+                    <th>{"Reduced cost"}</th>
+                    <th>{"Objective coefficient"}</th>
+                    <th>{"Allowable increase"}</th>
+                    <th>{"Allowable decrease"}</th>
+                </tr>
+                {
+                    for sensitivity.reduced_costs.iter().enumerate().map(|(j, (variable, cost))| {
+                        let range = sensitivity.objective_ranges.get(j);
+                        html! {
+                            <tr>
+                                <td>{variable}</td>
+                                <td>{values.get(j).map(|(_, v)| v.to_string()).unwrap_or_default()}</td>
+                                <td>{cost.to_string()}</td>
+                                <td>{range.map(|r| r.value.to_string()).unwrap_or_default()}</td>
+                                <td>{range.map(|r| limit(&r.increase)).unwrap_or_default()}</td>
+                                <td>{range.map(|r| limit(&r.decrease)).unwrap_or_default()}</td>
+                            </tr>
+                        }
+                    })
+                }
+            </table>
+            <table>
+                <tr>
+                    <th>{"Constraint"}</th>
+                    <th>{"Slack"}</th>
+                    <th>{"Shadow price"}</th>
+                    <th>{"Right-hand side"}</th>
+                    <th>{"Allowable increase"}</th>
+                    <th>{"Allowable decrease"}</th>
+                    <th>{"Binding"}</th>
+                </tr>
+                {
+                    for sensitivity.slacks.iter().enumerate().map(|(i, slack)| {
+                        let range = sensitivity.rhs_ranges.get(i);
+                        html! {
+                            <tr>
+                                <td>{i + 1}</td>
+                                <td>{slack.to_string()}</td>
+                                <td>{sensitivity.shadow_prices.get(i).map(|p| p.to_string()).unwrap_or_default()}</td>
+                                <td>{range.map(|r| r.value.to_string()).unwrap_or_default()}</td>
+                                <td>{range.map(|r| limit(&r.increase)).unwrap_or_default()}</td>
+                                <td>{range.map(|r| limit(&r.decrease)).unwrap_or_default()}</td>
+                                <td>{if sensitivity.binding[i] { "yes" } else { "no" }}</td>
+                            </tr>
+                        }
+                    })
+                }
+            </table>
+            </>
+        }
+    }
+
+    /// Best integer solution of branch and bound, with the bound and gap
+    /// that show how far from proven optimal it is.
+    fn view_integer_solution(&self) -> Html {
+        let Some(result) = &self.integer_result else {
+            return html! {
+                {"Run the algorithm to find the solution"}
+            };
+        };
+
+        let status = match result.status {
+            BranchStatus::Optimal => "Optimal integer solution",
+            BranchStatus::Infeasible => "No integer solution satisfies the constraints",
+            BranchStatus::Unbounded => "The relaxation is unbounded",
+            BranchStatus::NodeLimit => "Stopped at the node limit",
+            BranchStatus::IterationLimit => "Stopped at the iteration limit",
+        };
+        let optional = |value: &Option<Rational>| match value {
+            Some(value) => value.to_string(),
+            None => "∞".to_string(),
+        };
+
+        html! {
+            <>
+            <div>{format!("{} ({} nodes, {} iterations)", status, result.nodes, result.iterations)}</div>
+            if let Some(incumbent) = &result.incumbent {
+                <div>
+                {format!("Z = {} at ", incumbent.objective)}
+                {for incumbent.values.iter().map(|(var, val)| format!("{}={} ", var, val))}
+                </div>
+            }
+            <div>{format!("Bound: {}, gap: {}", optional(&result.bound), optional(&result.gap()))}</div>
+            </>
+        }
+    }
+
+    /// Every tableau of the last solve with its pivot highlighted and the
+    /// reason for it, the basis of the last one marked if it is optimal.
+    fn view_steps(&self) -> Html {
+        if self.trace.is_empty() {
+            return html! {
+                {"Run the algorithm to see its steps"}
+            };
+        }
+        let solved = matches!(self.result, Some(SolveResult::Optimal { .. }));
+
+        html! {
+            <ol>
+                {
+                    for self.trace.iter().enumerate().map(|(i, iteration)| html! {
+                        <li>
+                            <div>{iteration.to_string()}</div>
+                            <div class="explanation">{iteration.explanation()}</div>
+                            <TableauView iteration={iteration.clone()} show_pivot=true optimal={solved && i == self.trace.len() - 1}/>
+                        </li>
+                    })
+                }
+            </ol>
+        }
+    }
+
+    /// Applies the pivot chosen by hand if it is valid, and describes how it
+    /// compares with Dantzig's rule.
+    fn choose_pivot(stepper: &mut Stepper<Rational>, row: usize, column: usize) -> String {
+        let iteration = stepper.iteration();
+        let chosen = format!(
+            "{} entering, {} leaving",
+            iteration.columns[column], iteration.basis[row]
+        );
+        let follows_dantzig = stepper.follows_dantzig(row, column);
+        if let Err(error) = stepper.apply_pivot(row, column) {
+            return format!("{} is not a valid pivot: {}", chosen, error);
+        }
+        let verdict = if follows_dantzig {
+            "valid, and Dantzig's rule would pick it too".to_string()
+        } else {
+            let dantzig = match (&iteration.entering, &iteration.leaving) {
+                (Some(entering), Some(leaving)) => {
+                    format!("{} entering, {} leaving", entering, leaving)
+                }
+                _ => "another pivot".to_string(),
+            };
+            format!("valid, but Dantzig's rule would pick {}", dantzig)
+        };
+        format!("{} is {}", chosen, verdict)
+    }
+
+    /// Current tableau of the stepper and where it stands. Its cells are
+    /// clickable in manual mode.
+    fn view_stepper(&self, ctx: &yew::Context<Self>) -> Html {
+        let Some(stepper) = &self.stepper else {
+            return html! {
+                {"Start to pivot one step at a time"}
+            };
+        };
+        let on_select = (self.manual_pivots && stepper.next_pivot().is_some())
+            .then(|| ctx.link().callback(Msg::ChoosePivot));
+
+        html! {
+            <>
+            <div>{format!("{} after {} pivots: {}", stepper.stage(), stepper.steps(), stepper.status())}</div>
+            <TableauView iteration={stepper.iteration()} optimal={stepper.is_optimal()} {on_select}/>
+            if let Some(feedback) = &self.pivot_feedback {
+                <div>{feedback}</div>
+            }
+            <div>
+                {format!("Z = {} at ", stepper.objective())}
+                {for stepper.values().iter().map(|(var, val)| format!("{}={} ", var, val))}
+            </div>
+            </>
+        }
+    }
+
+    fn view_solution(&self) -> Html {
+        html! {
+            <>
+            { Self::view_result(&self.result, self.simplex.get_objective_sense(), "Z") }
+            if let Some(face) = self.optimal_face.as_ref().filter(|face| !face.is_unique()) {
+                <p>
+                    <b>{"Alternative optima. "}</b>
+                    {face.to_string()}
+                </p>
+            }
+            </>
+        }
+    }
+
+    /// The primal and dual models side by side, with the solution of the
+    /// dual and whether both optimal values match.
+    fn view_dual(&self) -> Html {
+        let dual = self.simplex.dual();
+        let view_model = |title: &str, model: &Simplex<Rational>| {
+            html! {
+                <div>
+                    <h4>{title}</h4>
+                    { for model.to_string().lines().map(|line| html! { <div>{line.to_string()}</div> }) }
+                </div>
+            }
+        };
+        let check = match (&self.result, &self.dual_result) {
+            (
+                Some(SolveResult::Optimal { objective: z, .. }),
+                Some(SolveResult::Optimal { objective: w, .. }),
+            ) if z == w => format!("Strong duality holds: Z = W = {}", z),
+            (
+                Some(SolveResult::Optimal { objective: z, .. }),
+                Some(SolveResult::Optimal { objective: w, .. }),
+            ) => format!("Z = {} and W = {} differ", z, w),
+            _ => String::new(),
+        };
+
+        html! {
+            <>
+            <div class="duality">
+                { view_model("Primal", &self.simplex) }
+                { view_model("Dual", &dual) }
+            </div>
+            { Self::view_result(&self.dual_result, dual.get_objective_sense(), "W") }
+            <div>{check}</div>
+            </>
+        }
+    }
+
+    /// Describes `result`, writing its objective value as `z`.
+    fn view_result(
+        result: &Option<SolveResult<Rational>>,
+        objective_sense: ObjectiveSense,
+        z: &str,
+    ) -> Html {
+        let view_values = |values: &Vec<(String, Rational)>| {
+            html! {
+                {for values.iter().map(|(var, val)|{
+                    html!{
+                        <>
+                            {format!("{}={} ", var, val)}
+                        </>
+                    }
+                })}
+            }
+        };
+
+        match result {
+            Some(SolveResult::Optimal {
+                iterations,
+                objective,
+                values,
+                ..
+            }) => html! {
+                <>
+                {format!("{} = {} at ", z, objective)}
+                {view_values(values)}
+                {format!("({} iterations)", iterations)}
+                </>
+            },
+            Some(SolveResult::Infeasible {
+                iterations,
+                infeasibility,
+            }) => html! {
+                {format!("The problem is infeasible: the constraints are violated by at least {} ({} iterations)", infeasibility, iterations)}
+            },
+            Some(SolveResult::Unbounded {
+                iterations,
+                variable,
+            }) => html! {
+                {format!("The problem is unbounded: {} {} without limit as {} increases ({} iterations)", z, match objective_sense { ObjectiveSense::Maximize => "grows", ObjectiveSense::Minimize => "decreases" }, variable, iterations)}
+            },
+            Some(SolveResult::IterationLimit {
+                iterations,
+                objective,
+                values,
+            }) => html! {
+                <>
+                {format!("Stopped after {} iterations without reaching the optimum. Last point: {} = {} at ", iterations, z, objective)}
+                {view_values(values)}
+                </>
+            },
+            None => html! {
+                {"Run the algorithm to find the solution"}
+            },
+        }
+    }
+}
 
 impl Default for App {
     fn default() -> Self {
@@ -38,9 +484,22 @@ impl Default for App {
             current_variable: Default::default(),
             target_inputs: vec![],
             constraint_inputs: vec!["".to_string()],
+            constraint_relation: Relation::default(),
             simplex: Default::default(),
-            solution: None,
-            final_values: Default::default(),
+            result: None,
+            optimal_face: None,
+            show_steps: false,
+            trace: vec![],
+            stepper: None,
+            manual_pivots: false,
+            pivot_feedback: None,
+            integer_result: None,
+            cutting_planes: None,
+            show_dual: false,
+            dual_result: None,
+            knapsack_weights: Default::default(),
+            knapsack_values: Default::default(),
+            knapsack_capacity: Default::default(),
             theme: Default::default(),
         }
     }
@@ -58,19 +517,45 @@ impl Component for App {
     fn update(&mut self, _ctx: &yew::Context<Self>, msg: Self::Message) -> bool {
         match msg {
             Msg::VariableChange(text) => self.current_variable = text,
-            Msg::ConstraintChange((text, i)) => {
-                self.constraint_inputs[i] = text;
-                web_sys::console::log_1(&format!("{:?}", self.constraint_inputs).into());
+            Msg::LowerBoundChange((text, i)) => {
+                let mut bounds = self.simplex.get_bounds()[i].clone();
+                bounds.lower = match text.trim() {
+                    "" => Some(Rational::zero()),
+                    "-inf" | "-∞" => None,
+                    text => match text.parse::<Rational>() {
+                        Ok(v) => Some(v),
+                        Err(_) => return false,
+                    },
+                };
+                self.simplex.set_bounds(i, bounds);
+            }
+            Msg::UpperBoundChange((text, i)) => {
+                let mut bounds = self.simplex.get_bounds()[i].clone();
+                bounds.upper = match text.trim() {
+                    "" | "inf" | "∞" => None,
+                    text => match text.parse::<Rational>() {
+                        Ok(v) => Some(v),
+                        Err(_) => return false,
+                    },
+                };
+                self.simplex.set_bounds(i, bounds);
             }
+            Msg::ChangeVariableKind(i) => {
+                let kind = self.simplex.get_variable_kinds()[i];
+                self.simplex.set_variable_kind(i, kind.next());
+            }
+            Msg::ConstraintChange((text, i)) => self.constraint_inputs[i] = text,
+            Msg::RelationChange => self.constraint_relation = self.constraint_relation.next(),
             Msg::TargetChange((text, i)) => self.target_inputs[i] = text,
             Msg::AddVariable => {
-                if self.current_variable != "".to_string()
+                if !self.current_variable.is_empty()
                     && !self
                         .simplex
                         .get_variables()
                         .contains(&self.current_variable)
                 {
-                    self.simplex.add_variable(self.current_variable.clone());
+                    self.simplex
+                        .add_variable(self.current_variable.clone(), VariableKind::Continuous);
                     self.current_variable = "".to_string();
                     for _ in
                         0..(1 + self.simplex.get_variables().len() - self.constraint_inputs.len())
@@ -82,20 +567,18 @@ impl Component for App {
                     {
                         self.target_inputs.push("".to_string());
                     }
-                    web_sys::console::log_1(&format!("{:?}", self.simplex.get_variables()).into());
-                    web_sys::console::log_1(&format!("{:?}", self.constraint_inputs).into());
                 }
             }
             Msg::ClearVariables => self.simplex.clear_variables(),
             Msg::AddConstraint => {
-                let mut constraint = vec![0_f32; self.constraint_inputs.len()];
+                let mut constraint = vec![Rational::zero(); self.constraint_inputs.len()];
                 let mut is_ok = true;
                 for (i, input) in self.constraint_inputs.iter().enumerate() {
-                    if *input == "".to_string() {
-                        constraint[i] = 0_f32;
+                    if input.is_empty() {
+                        constraint[i] = Rational::zero();
                         continue;
                     }
-                    if let Ok(v) = input.parse::<f32>() {
+                    if let Ok(v) = input.parse::<Rational>() {
                         constraint[i] = v;
                     } else {
                         is_ok = false;
@@ -104,36 +587,134 @@ impl Component for App {
                 }
 
                 if is_ok {
-                    self.simplex.add_constraint(constraint);
+                    self.simplex
+                        .add_constraint(constraint, self.constraint_relation);
                 }
             }
             Msg::ClearConstraints => self.simplex.clear_constraint(), //self.simplex.clear_constraint(),
             Msg::RunSimplex => {
-                let mut target = vec![0_f32; self.target_inputs.len()];
-                let mut is_ok = true;
-                for (i, input) in self.target_inputs.iter().enumerate() {
-                    if *input == "".to_string() {
-                        target[i] = 0_f32;
-                        continue;
-                    }
-                    if let Ok(v) = input.parse::<f32>() {
-                        target[i] = v;
-                    } else {
-                        is_ok = false;
-                        break;
+                self.read_target();
+                if self.show_steps {
+                    let (result, trace) = self.simplex.run_traced();
+                    self.result = Some(result);
+                    self.trace = trace;
+                } else {
+                    self.result = Some(self.simplex.run_simplex());
+                    self.trace = vec![];
+                }
+                self.optimal_face = match &self.result {
+                    Some(SolveResult::Optimal { sensitivity, .. })
+                        if sensitivity.alternative_optima =>
+                    {
+                        self.simplex.optimal_face(MAX_OPTIMAL_VERTICES)
                     }
+                    _ => None,
+                };
+                self.integer_result = if self
+                    .simplex
+                    .get_variable_kinds()
+                    .iter()
+                    .any(|k| k.is_integer())
+                {
+                    Some(self.simplex.run_branch_and_bound())
+                } else {
+                    None
+                };
+                if self.show_dual {
+                    self.dual_result = Some(self.simplex.dual().run_simplex());
                 }
-
-                if is_ok {
-                    self.simplex.set_target(target);
+            }
+            Msg::RunGomory => {
+                self.read_target();
+                self.cutting_planes = Some(self.simplex.run_gomory(MAX_GOMORY_CUTS));
+            }
+            Msg::ChangeObjectiveSense => {
+                let objective_sense = match self.simplex.get_objective_sense() {
+                    ObjectiveSense::Maximize => ObjectiveSense::Minimize,
+                    ObjectiveSense::Minimize => ObjectiveSense::Maximize,
+                };
+                self.simplex.set_objective_sense(objective_sense);
+            }
+            Msg::ChangeAlgorithm => {
+                self.simplex
+                    .set_algorithm(self.simplex.get_algorithm().next());
+            }
+            Msg::ChangeStartMethod => {
+                let start_method = match self.simplex.get_start_method() {
+                    StartMethod::TwoPhase => StartMethod::BigM,
+                    StartMethod::BigM => StartMethod::TwoPhase,
+                };
+                self.simplex.set_start_method(start_method);
+            }
+            Msg::ChangePricingRule => {
+                self.simplex
+                    .set_pricing_rule(self.simplex.get_pricing_rule().next());
+            }
+            Msg::ChangeAntiCycling => {
+                self.simplex
+                    .set_anti_cycling(self.simplex.get_anti_cycling().next());
+            }
+            Msg::ChangeNodeSelection => {
+                self.simplex
+                    .set_node_selection(self.simplex.get_node_selection().next());
+            }
+            Msg::ChangeBranchingRule => {
+                self.simplex
+                    .set_branching_rule(self.simplex.get_branching_rule().next());
+            }
+            Msg::ToggleDual => {
+                self.show_dual = !self.show_dual;
+                self.dual_result = match (&self.result, self.show_dual) {
+                    (Some(_), true) => Some(self.simplex.dual().run_simplex()),
+                    _ => None,
+                };
+            }
+            Msg::ToggleSteps => {
+                self.show_steps = !self.show_steps;
+                self.trace = match (&self.result, self.show_steps) {
+                    (Some(_), true) => self.simplex.run_traced().1,
+                    _ => vec![],
+                };
+            }
+            Msg::KnapsackWeightsChange(text) => self.knapsack_weights = text,
+            Msg::KnapsackValuesChange(text) => self.knapsack_values = text,
+            Msg::KnapsackCapacityChange(text) => self.knapsack_capacity = text,
+            Msg::LoadKnapsack => return self.load_knapsack(),
+            Msg::StartStepper => {
+                self.read_target();
+                self.stepper = self.simplex.stepper();
+                self.pivot_feedback = None;
+            }
+            Msg::NextStep => {
+                let Some(stepper) = &mut self.stepper else {
+                    return false;
+                };
+                let Some(pivot) = stepper.next_pivot() else {
+                    return false;
+                };
+                if stepper.apply_pivot(pivot.row, pivot.column).is_err() {
+                    return false;
                 }
-
-                let (sol, values) = self.simplex.run_simplex();
-                self.solution = Some(sol);
-                self.final_values = values;
+                self.pivot_feedback = None;
+            }
+            Msg::PreviousStep => {
+                if !self.stepper.as_mut().is_some_and(|stepper| stepper.undo()) {
+                    return false;
+                }
+                self.pivot_feedback = None;
+            }
+            Msg::ToggleManualPivots => {
+                self.manual_pivots = !self.manual_pivots;
+                self.pivot_feedback = None;
+            }
+            Msg::ChoosePivot((row, column)) => {
+                let Some(stepper) = &mut self.stepper else {
+                    return false;
+                };
+                self.pivot_feedback = Some(Self::choose_pivot(stepper, row, column));
             }
             Msg::ChangeTheme => {
-                if self.theme == "" {
+                if self.theme.is_empty() {
                     self.theme = "dark-theme".to_string()
                 } else {
                     self.theme = "".to_string()
@@ -145,13 +726,36 @@ impl Component for App {
 
     fn view(&self, ctx: &yew::Context<Self>) -> yew::Html {
         let on_change_variable = ctx.link().callback(Msg::VariableChange);
+        let on_change_lower = ctx.link().callback(Msg::LowerBoundChange);
+        let on_change_upper = ctx.link().callback(Msg::UpperBoundChange);
         let on_change_constraint = ctx.link().callback(Msg::ConstraintChange);
+        let on_change_relation = ctx.link().callback(|_| Msg::RelationChange);
         let on_change_target = ctx.link().callback(Msg::TargetChange);
         let add_variable = ctx.link().callback(|_| Msg::AddVariable);
         let clear_variables = ctx.link().callback(|_| Msg::ClearVariables);
         let clear_constraint = ctx.link().callback(|_| Msg::ClearConstraints);
         let add_constraint = ctx.link().callback(|_| Msg::AddConstraint);
         let run_simplex = ctx.link().callback(|_| Msg::RunSimplex);
+        let change_objective_sense = ctx.link().callback(|_| Msg::ChangeObjectiveSense);
+        let change_algorithm = ctx.link().callback(|_| Msg::ChangeAlgorithm);
+        let change_start_method = ctx.link().callback(|_| Msg::ChangeStartMethod);
+        let change_pricing_rule = ctx.link().callback(|_| Msg::ChangePricingRule);
+        let change_anti_cycling = ctx.link().callback(|_| Msg::ChangeAntiCycling);
+        let change_node_selection = ctx.link().callback(|_| Msg::ChangeNodeSelection);
+        let change_branching_rule = ctx.link().callback(|_| Msg::ChangeBranchingRule);
+        let toggle_dual = ctx.link().callback(|_| Msg::ToggleDual);
+        let toggle_steps = ctx.link().callback(|_| Msg::ToggleSteps);
+        let kinds = self.simplex.get_variable_kinds();
+        let any_integer = kinds.iter().any(|k| k.is_integer());
+        let on_change_weights = ctx.link().callback(Msg::KnapsackWeightsChange);
+        let on_change_values = ctx.link().callback(Msg::KnapsackValuesChange);
+        let on_change_capacity = ctx.link().callback(Msg::KnapsackCapacityChange);
+        let load_knapsack = ctx.link().callback(|_| Msg::LoadKnapsack);
+        let run_gomory = ctx.link().callback(|_| Msg::RunGomory);
+        let start_stepper = ctx.link().callback(|_| Msg::StartStepper);
+        let next_step = ctx.link().callback(|_| Msg::NextStep);
+        let previous_step = ctx.link().callback(|_| Msg::PreviousStep);
+        let toggle_manual_pivots = ctx.link().callback(|_| Msg::ToggleManualPivots);
         let change_theme = ctx.link().callback(|_| Msg::ChangeTheme);
 
         html! {
@@ -166,14 +770,14 @@ impl Component for App {
             <div style="display: flex; align-items: center; gap: 8px;">
             <span><b>{"Source in"}</b></span>
             <a href="https://github.com/Guilleag01/simplex_web" target="_blank">
-                <img src={if self.theme == "" {"img/GitHub_Invertocat_Dark.svg"} else {"img/GitHub_Invertocat_Light.svg"}}
+                <img src={if self.theme.is_empty() {"img/GitHub_Invertocat_Dark.svg"} else {"img/GitHub_Invertocat_Light.svg"}}
                     alt="GitHub Logo"
                     style="width: 24px; height: 24px;"/>
             </a>
             </div>
 
             <button onclick={change_theme} id="theme-toggle" class="theme-btn" title="Toggle Dark/Light Mode">
-            { if self.theme == "".to_string() { "🌙" } else { "☀️" } }
+            { if self.theme.is_empty() { "🌙" } else { "☀️" } }
             </button>
         </header>
         <div class="container">
@@ -196,9 +800,16 @@ impl Component for App {
                 </div>
                 <ul>
                     {
-                        for self.simplex.get_variables().iter().map(|v| {
+                        for self.simplex.get_variables().iter().enumerate().map(|(i, v)| {
+                            let change_kind = ctx.link().callback(move |_| Msg::ChangeVariableKind(i));
                             html! {
-                                <li>{v}</li>
+                                <li>
+                                  {v}
+                                  <BoundInput variable={v.clone()} index={i} on_change_lower={on_change_lower.clone()} on_change_upper={on_change_upper.clone()}/>
+                                  <button onclick={change_kind} title="Switch between continuous, integer and binary values">
+                                    {kinds[i].to_string()}
+                                  </button>
+                                </li>
                             }
                         })
                     }
@@ -209,26 +820,26 @@ impl Component for App {
               <div class="sub-section">
                 <h3>{"Constraints"}</h3>
                 <div class="input-row">
-                  <ConstraintInput {on_change_constraint} variables={self.simplex.get_variables()}/>
+                  <ConstraintInput {on_change_constraint} {on_change_relation} relation={self.constraint_relation} variables={self.simplex.get_variables()}/>
                   <button onclick={add_constraint}>{"Add"}</button>
                   <button onclick={clear_constraint}>{"Clear"}</button>
                 </div>
                 <ul>
                     {
-                        for self.simplex.get_constraints().iter().map(|c| {
+                        for zip(self.simplex.get_constraints().iter(), self.simplex.get_relations()).map(|(c, relation)| {
                             html! {
                                 <li>
                                 {
                                     for zip(c, self.simplex.get_variables()).take(c.len() - 1).enumerate().map(|(i, (v, var))| {
-                                        let v_2 = if i > 0 { v.abs() } else { *v };
+                                        let v_2 = if i > 0 { v.abs() } else { v.clone() };
                                         html! {
-                                            { format!("{}{} {} ", if v_2 == 1_f32 { "".to_string() } else {if v_2 == -1_f32 {" -".to_string()} else { format!("{}", v_2) }}, var, {if i < c.len() - 2 {if c[i + 1] < 0_f32 {" - "} else {" + "}} else { " " }} ) }
+                                            { format!("{}{} {} ", if v_2 == Rational::one() { "".to_string() } else {if v_2 == -Rational::one() {" -".to_string()} else { format!("{}", v_2) }}, var, {if i < c.len() - 2 {if c[i + 1].is_negative() {" - "} else {" + "}} else { " " }} ) }
                                         }
                                     }
                                 )}
 
                                 {
-                                    format!(" ≤ {}", c[c.len() - 1])
+                                    format!(" {} {}", relation, c[c.len() - 1])
                                 }
                                 </li>
                             }
@@ -237,11 +848,52 @@ impl Component for App {
 
                 </ul>
               </div>
+            //   <!-- Knapsack -->
+              <div class="sub-section">
+                <h3>{"Knapsack"}</h3>
+                <div class="input-row">
+                  <VariableInput on_change_variable={on_change_weights} value={self.knapsack_weights.clone()} placeholder="Weights (e.g., 3, 4, 2)"/>
+                  <VariableInput on_change_variable={on_change_values} value={self.knapsack_values.clone()} placeholder="Values (e.g., 5, 6, 3)"/>
+                  <VariableInput on_change_variable={on_change_capacity} value={self.knapsack_capacity.clone()} placeholder="Capacity"/>
+                  <button onclick={load_knapsack} title="Replace the problem with this 0-1 knapsack">{"Load"}</button>
+                </div>
+              </div>
               <div class="sub-section objective">
               <div>
-                <h3>{"Target Function"}</h3>
-                <TargetInput {on_change_target} variables={self.simplex.get_variables()}/>
+                <h3>
+                  {"Target Function "}
+                  <button onclick={change_objective_sense} title="Switch between maximizing and minimizing">
+                    {self.simplex.get_objective_sense().to_string()}
+                  </button>
+                </h3>
+                <TargetInput {on_change_target} variables={self.simplex.get_variables()} values={self.target_inputs.clone()}/>
               </div>
+              <button onclick={change_algorithm} title="Change the variant of the simplex method">
+                {format!("Algorithm: {}", self.simplex.get_algorithm())}
+              </button>
+              <button onclick={change_start_method} title="Change how the initial basis is found">
+                {format!("Method: {}", self.simplex.get_start_method())}
+              </button>
+              <button onclick={change_pricing_rule} title="Change how the entering variable is chosen">
+                {format!("Pricing: {}", self.simplex.get_pricing_rule())}
+              </button>
+              <button onclick={change_anti_cycling} title="Change how degenerate pivots are kept from cycling">
+                {format!("Anti-cycling: {}", self.simplex.get_anti_cycling())}
+              </button>
+              if any_integer {
+                <button onclick={change_node_selection} title="Change which branch-and-bound node is solved next">
+                  {format!("Nodes: {}", self.simplex.get_node_selection())}
+                </button>
+                <button onclick={change_branching_rule} title="Change which fractional variable is branched on">
+                  {format!("Branching: {}", self.simplex.get_branching_rule())}
+                </button>
+                <button onclick={run_gomory} title="Solve by adding Gomory fractional cuts to the relaxation">
+                  {"Gomory cuts"}
+                </button>
+              }
+              <button onclick={toggle_dual} title="Show the dual problem and solve it too">
+                {if self.show_dual { "Hide dual" } else { "Show dual" }}
+              </button>
               <button onclick={run_simplex}>{"Run Simplex"}</button>
             </div>
             </div>
@@ -253,35 +905,61 @@ impl Component for App {
           // <!-- Bottom: Output Section -->
           <div class="output-section">
             <div class="steps">
-              <h3>{"Steps"}</h3>
-              <ul>
-                <li>{"Step 1: Initial Tableau"}</li>
-              </ul>
+              <h3>
+                {"Steps "}
+                <button onclick={toggle_steps} title="Record every tableau of the solve">
+                  {if self.show_steps { "Hide steps" } else { "Show steps" }}
+                </button>
+              </h3>
+              if self.show_steps {
+                { self.view_steps() }
+              }
             </div>
             <div class="final-result">
-            <h3>{"Solution"}</h3>
+            <h3>
+              {"Step by step "}
+              <button onclick={start_stepper} title="Start pivoting the problem by hand from its initial tableau">
+                {if self.stepper.is_some() { "Restart" } else { "Start" }}
+              </button>
+              <button onclick={previous_step} disabled={self.stepper.as_ref().is_none_or(|s| s.steps() == 0)}>
+                {"Previous step"}
+              </button>
+              <button onclick={next_step} disabled={self.stepper.as_ref().is_none_or(|s| s.next_pivot().is_none())}>
+                {"Next step"}
+              </button>
+              <button onclick={toggle_manual_pivots} title="Choose each pivot by clicking a cell of the tableau">
+                {if self.manual_pivots { "Manual pivots: on" } else { "Manual pivots: off" }}
+              </button>
+            </h3>
+                { self.view_stepper(ctx) }
+            </div>
+            <div class="final-result">
+            <h3>{if any_integer { "LP relaxation" } else { "Solution" }}</h3>
             //  {" Final Result: Z = 50 at (x=10, y=5)"}
-                {
-                    if let Some(solution) = self.solution {
-                        html! {
-                            <>
-                            {format!("Z = {} at ", solution)}
-                                {for self.final_values.iter().map(|(var, val)|{
-                                    html!{
-                                        <>
-                                            {format!("{}={} ", var, val)}
-                                        </>
-                                    }
-                                })}
-                            </>
-                        }
-                    } else {
-                        html!{
-                            {"Run the algorithm to find the solution"}
-                        }
-                    }
-                }
+                { self.view_solution() }
+            </div>
+            if any_integer {
+                <div class="final-result">
+                <h3>{"Integer solution"}</h3>
+                    { self.view_integer_solution() }
+                </div>
+            }
+            if self.cutting_planes.is_some() {
+                <div class="final-result sensitivity">
+                <h3>{"Cutting planes"}</h3>
+                    { self.view_cutting_planes() }
+                </div>
+            }
+            <div class="final-result sensitivity">
+            <h3>{"Sensitivity"}</h3>
+                { self.view_sensitivity() }
             </div>
+            if self.show_dual {
+                <div class="final-result sensitivity">
+                <h3>{"Duality"}</h3>
+                    { self.view_dual() }
+                </div>
+            }
 
           </div>
         </div>
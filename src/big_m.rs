@@ -0,0 +1,195 @@
+use std::{
+    fmt,
+    ops::{Add, Div, Mul, Neg, Sub},
+};
+
+use crate::scalar::Scalar;
+
+/// A value of the form `m·M + c`, where `M` is the symbolic "very large"
+/// penalty of the Big-M method.
+///
+/// Only the objective row of a Big-M tableau ever has a non-zero `m` part, so
+/// products and quotients are only defined when at least one side (the divisor
+/// for quotients) is a plain number.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BigM<T> {
+    pub m: T,
+    pub c: T,
+}
+
+impl<T: Scalar> BigM<T> {
+    pub fn new(m: T, c: T) -> Self {
+        Self { m, c }
+    }
+
+    /// A plain number, without any `M` part.
+    pub fn constant(c: T) -> Self {
+        Self { m: T::zero(), c }
+    }
+
+    pub fn is_constant(&self) -> bool {
+        self.m.is_zero()
+    }
+}
+
+impl<T: Scalar> Scalar for BigM<T> {
+    fn zero() -> Self {
+        Self::constant(T::zero())
+    }
+
+    fn one() -> Self {
+        Self::constant(T::one())
+    }
+
+    fn from_f64(value: f64) -> Self {
+        Self::constant(T::from_f64(value))
+    }
+
+    fn to_f64(&self) -> f64 {
+        debug_assert!(self.is_constant(), "approximating a term in M");
+        self.c.to_f64()
+    }
+
+    /// The sign of the `M` part, up to the tolerance of `T`, outweighs any
+    /// constant; the constant only decides when there is no `M` part.
+    fn is_negative(&self) -> bool {
+        self.m.is_negative() || (!self.m.is_positive() && self.c.is_negative())
+    }
+
+    fn is_positive(&self) -> bool {
+        self.m.is_positive() || (!self.m.is_negative() && self.c.is_positive())
+    }
+
+    fn floor(&self) -> Self {
+        debug_assert!(self.is_constant(), "rounding a term in M");
+        Self::constant(self.c.floor())
+    }
+}
+
+impl<T: Scalar> PartialOrd for BigM<T> {
+    /// Orders by the `M` part first. Parts that differ only by rounding noise
+    /// count as equal, so a leftover `0.0000001M` cannot outweigh the
+    /// constant part.
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        let difference = self.clone() - other.clone();
+        if difference.is_negative() {
+            Some(std::cmp::Ordering::Less)
+        } else if difference.is_positive() {
+            Some(std::cmp::Ordering::Greater)
+        } else {
+            Some(std::cmp::Ordering::Equal)
+        }
+    }
+}
+
+impl<T: Scalar> Add for BigM<T> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self::new(self.m + rhs.m, self.c + rhs.c)
+    }
+}
+
+impl<T: Scalar> Sub for BigM<T> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Self::new(self.m - rhs.m, self.c - rhs.c)
+    }
+}
+
+impl<T: Scalar> Mul for BigM<T> {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        debug_assert!(
+            self.is_constant() || rhs.is_constant(),
+            "M² term in tableau"
+        );
+        Self::new(
+            self.m * rhs.c.clone() + self.c.clone() * rhs.m,
+            self.c * rhs.c,
+        )
+    }
+}
+
+impl<T: Scalar> Div for BigM<T> {
+    type Output = Self;
+
+    fn div(self, rhs: Self) -> Self {
+        debug_assert!(rhs.is_constant(), "division by a term in M");
+        Self::new(self.m / rhs.c.clone(), self.c / rhs.c)
+    }
+}
+
+impl<T: Scalar> Neg for BigM<T> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self::new(-self.m, -self.c)
+    }
+}
+
+impl<T: Scalar> fmt::Display for BigM<T> {
+    /// Prints values like `3M - 2`, `-M`, `1/2M + 4` or `4`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_constant() {
+            return write!(f, "{}", self.c);
+        }
+
+        if self.m == T::one() {
+            write!(f, "M")?;
+        } else if self.m == -T::one() {
+            write!(f, "-M")?;
+        } else {
+            write!(f, "{}M", self.m)?;
+        }
+
+        if self.c.is_positive() {
+            write!(f, " + {}", self.c)
+        } else if self.c.is_negative() {
+            write!(f, " - {}", self.c.abs())
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rational::Rational;
+
+    fn big_m(m: i64, c: i64) -> BigM<Rational> {
+        BigM::new(Rational::from(m), Rational::from(c))
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(big_m(3, -2).to_string(), "3M - 2");
+        assert_eq!(big_m(-1, 0).to_string(), "-M");
+        assert_eq!(big_m(1, 5).to_string(), "M + 5");
+        assert_eq!(
+            BigM::new(Rational::new(1, 2), Rational::from(4)).to_string(),
+            "1/2M + 4"
+        );
+        assert_eq!(big_m(0, -4).to_string(), "-4");
+    }
+
+    #[test]
+    fn orders_by_the_m_part_first() {
+        assert!(big_m(3, -2) > big_m(2, 1000));
+        assert!(big_m(-1, 1000) < big_m(0, -1000));
+        assert!(big_m(1, -2) < big_m(1, 3));
+        assert!(big_m(0, 0) == BigM::zero());
+        assert!(big_m(3, -2).is_positive());
+        assert!(big_m(-1, 1000).is_negative());
+    }
+
+    #[test]
+    fn float_noise_in_the_m_part_does_not_outweigh_the_constant() {
+        let noisy = BigM::new(1e-12, -1.0);
+        assert!(noisy.is_negative());
+        assert!(noisy < BigM::constant(0.0));
+    }
+}
@@ -0,0 +1,42 @@
+use web_sys::{HtmlInputElement, wasm_bindgen::JsCast};
+use yew::{Callback, Event, Html, InputEvent, Properties, function_component, html};
+
+#[derive(Clone, PartialEq, Properties)]
+pub struct Props {
+    pub variable: String,
+    pub index: usize,
+    pub on_change_lower: Callback<(String, usize)>,
+    pub on_change_upper: Callback<(String, usize)>,
+}
+
+fn get_value_from_input_event(e: InputEvent) -> String {
+    let event: Event = e.dyn_into().unwrap();
+    let event_target = event.target().unwrap();
+    let target: HtmlInputElement = event_target.dyn_into().unwrap();
+    target.value()
+}
+
+#[function_component(BoundInput)]
+pub fn bound_input(properties: &Props) -> Html {
+    let Props {
+        variable,
+        index,
+        on_change_lower,
+        on_change_upper,
+    } = properties.clone();
+
+    let oninput_lower = Callback::from(move |input_event: InputEvent| {
+        on_change_lower.emit((get_value_from_input_event(input_event), index));
+    });
+    let oninput_upper = Callback::from(move |input_event: InputEvent| {
+        on_change_upper.emit((get_value_from_input_event(input_event), index));
+    });
+
+    html! {
+        <span class="bounds">
+            <input type="text" oninput={oninput_lower} placeholder="0" title="Lower bound, -inf for none"/>
+            {format!(" ≤ {} ≤ ", variable)}
+            <input type="text" oninput={oninput_upper} placeholder="inf" title="Upper bound, empty for none"/>
+        </span>
+    }
+}
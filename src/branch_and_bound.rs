@@ -0,0 +1,464 @@
+use std::fmt;
+
+use crate::{
+    scalar::Scalar,
+    simplex::{Bounds, ObjectiveSense, Simplex, SolveResult},
+};
+
+/// Nodes [`Simplex::run_branch_and_bound`] solves before giving up on proving
+/// optimality.
+pub(crate) const DEFAULT_MAX_NODES: usize = 1000;
+
+/// Order in which the open nodes of the branch-and-bound tree are solved.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum NodeSelection {
+    /// The node created last, diving down one branch to find an integer
+    /// solution early. Of the two children of a node, the one on the side
+    /// the value is closer to comes first.
+    #[default]
+    DepthFirst,
+    /// The node whose parent relaxation has the best objective value, which
+    /// tightens the bound fastest.
+    BestBound,
+}
+
+impl NodeSelection {
+    /// Next selection in the `DepthFirst → BestBound` cycle, used by the UI
+    /// toggle.
+    pub fn next(self) -> Self {
+        match self {
+            NodeSelection::DepthFirst => NodeSelection::BestBound,
+            NodeSelection::BestBound => NodeSelection::DepthFirst,
+        }
+    }
+}
+
+impl fmt::Display for NodeSelection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NodeSelection::DepthFirst => write!(f, "Depth-first"),
+            NodeSelection::BestBound => write!(f, "Best bound"),
+        }
+    }
+}
+
+/// Rule choosing which fractional integer variable a node branches on.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum BranchingRule {
+    /// The variable whose fractional part is closest to `1/2`.
+    #[default]
+    MostFractional,
+    /// The variable closest to an integer.
+    LeastFractional,
+    /// The first fractional variable, in the order they were added.
+    FirstFractional,
+}
+
+impl BranchingRule {
+    pub const ALL: [BranchingRule; 3] = [
+        BranchingRule::MostFractional,
+        BranchingRule::LeastFractional,
+        BranchingRule::FirstFractional,
+    ];
+
+    /// Next rule in [`BranchingRule::ALL`], used by the UI toggle.
+    pub fn next(self) -> Self {
+        let i = Self::ALL.iter().position(|r| *r == self).unwrap();
+        Self::ALL[(i + 1) % Self::ALL.len()]
+    }
+}
+
+impl fmt::Display for BranchingRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BranchingRule::MostFractional => write!(f, "Most fractional"),
+            BranchingRule::LeastFractional => write!(f, "Least fractional"),
+            BranchingRule::FirstFractional => write!(f, "First fractional"),
+        }
+    }
+}
+
+/// Why [`Simplex::run_branch_and_bound`] stopped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BranchStatus {
+    /// The incumbent is optimal.
+    Optimal,
+    /// No integer point satisfies every constraint.
+    Infeasible,
+    /// A relaxation is unbounded, so the problem is either unbounded or has
+    /// no integer solution at all.
+    Unbounded,
+    /// Every allowed node was solved with some still open.
+    NodeLimit,
+    /// A relaxation reached the iteration limit.
+    IterationLimit,
+}
+
+/// Best integer solution found so far.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Incumbent<T> {
+    pub objective: T,
+    pub values: Vec<(String, T)>,
+}
+
+/// Outcome of [`Simplex::run_branch_and_bound`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct BranchAndBound<T> {
+    pub status: BranchStatus,
+    pub incumbent: Option<Incumbent<T>>,
+    /// Best objective value an integer solution could still have, or `None`
+    /// if the open nodes do not limit it.
+    pub bound: Option<T>,
+    /// Relaxations solved.
+    pub nodes: usize,
+    /// Pivots over every relaxation.
+    pub iterations: usize,
+}
+
+impl<T: Scalar> BranchAndBound<T> {
+    /// Distance between the bound and the incumbent, zero once the incumbent
+    /// is proven optimal.
+    pub fn gap(&self) -> Option<T> {
+        match (&self.incumbent, &self.bound) {
+            (Some(incumbent), Some(bound)) => {
+                Some((bound.clone() - incumbent.objective.clone()).abs())
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Open node of the tree: the bounds of its subproblem and the objective
+/// value of its parent relaxation, which no solution below it can beat.
+struct Node<T> {
+    bounds: Vec<Bounds<T>>,
+    bound: Option<T>,
+}
+
+impl<T: Scalar> Simplex<T> {
+    /// Solves the problem with the integer and binary variables, as set by
+    /// [`Simplex::set_variable_kind`], restricted to integer values.
+    ///
+    /// Every node solves the LP relaxation of its subproblem with
+    /// [`Simplex::run_simplex`]. A node whose relaxation cannot beat the
+    /// incumbent is pruned, and one with a fractional integer variable `x = v`
+    /// branches into `x ≤ ⌊v⌋` and `x ≥ ⌊v⌋ + 1` by tightening its bounds.
+    pub fn run_branch_and_bound(&self) -> BranchAndBound<T> {
+        // Objective values compared as if maximizing.
+        let maximized = |value: &T| match self.get_objective_sense() {
+            ObjectiveSense::Maximize => value.clone(),
+            ObjectiveSense::Minimize => -value.clone(),
+        };
+        let improves = |value: &T, incumbent: &Option<Incumbent<T>>| match incumbent {
+            Some(incumbent) => (maximized(value) - maximized(&incumbent.objective)).is_positive(),
+            None => true,
+        };
+
+        let mut open = vec![Node {
+            bounds: self.get_bounds(),
+            bound: None,
+        }];
+        let mut incumbent: Option<Incumbent<T>> = None;
+        let mut relaxation = self.clone();
+        let (mut nodes, mut iterations) = (0, 0);
+
+        let status = loop {
+            open.retain(|node| {
+                node.bound
+                    .as_ref()
+                    .is_none_or(|bound| improves(bound, &incumbent))
+            });
+            let Some(node) = self.select_node(&mut open, &maximized) else {
+                break match incumbent {
+                    Some(_) => BranchStatus::Optimal,
+                    None => BranchStatus::Infeasible,
+                };
+            };
+            if nodes == self.get_max_nodes() {
+                open.push(node);
+                break BranchStatus::NodeLimit;
+            }
+
+            nodes += 1;
+            for (j, bounds) in node.bounds.iter().enumerate() {
+                relaxation.set_bounds(j, bounds.clone());
+            }
+            let result = relaxation.run_simplex();
+            iterations += result.iterations();
+            let (objective, values) = match result {
+                SolveResult::Optimal {
+                    objective, values, ..
+                } => (objective, values),
+                SolveResult::Infeasible { .. } => continue,
+                SolveResult::Unbounded { .. } => {
+                    open.push(node);
+                    break BranchStatus::Unbounded;
+                }
+                SolveResult::IterationLimit { .. } => {
+                    open.push(node);
+                    break BranchStatus::IterationLimit;
+                }
+            };
+            if !improves(&objective, &incumbent) {
+                continue;
+            }
+
+            let Some(j) = self.branching_variable(&values) else {
+                incumbent = Some(Incumbent { objective, values });
+                continue;
+            };
+            let value = values[j].1.clone();
+            let floor = value.floor();
+
+            let mut down = node.bounds.clone();
+            down[j].upper = Some(floor.clone());
+            let mut up = node.bounds;
+            up[j].lower = Some(floor.clone() + T::one());
+            let down = Node {
+                bounds: down,
+                bound: Some(objective.clone()),
+            };
+            let up = Node {
+                bounds: up,
+                bound: Some(objective),
+            };
+            // The child pushed last is the one depth-first explores next.
+            let half = T::one() / (T::one() + T::one());
+            if value - floor > half {
+                open.extend([down, up]);
+            } else {
+                open.extend([up, down]);
+            }
+        };
+
+        let bound = match status {
+            BranchStatus::Optimal => incumbent.as_ref().map(|i| i.objective.clone()),
+            BranchStatus::Infeasible | BranchStatus::Unbounded => None,
+            BranchStatus::NodeLimit | BranchStatus::IterationLimit => {
+                let mut bound = incumbent.as_ref().map(|i| i.objective.clone());
+                for node in open.iter() {
+                    match &node.bound {
+                        None => {
+                            bound = None;
+                            break;
+                        }
+                        Some(b) if bound.as_ref().is_none_or(|c| maximized(b) > maximized(c)) => {
+                            bound = Some(b.clone())
+                        }
+                        Some(_) => {}
+                    }
+                }
+                bound
+            }
+        };
+
+        BranchAndBound {
+            status,
+            incumbent,
+            bound,
+            nodes,
+            iterations,
+        }
+    }
+
+    /// Takes the next node to solve out of `open`, as the configured
+    /// [`NodeSelection`] says.
+    fn select_node(
+        &self,
+        open: &mut Vec<Node<T>>,
+        maximized: &impl Fn(&T) -> T,
+    ) -> Option<Node<T>> {
+        match self.get_node_selection() {
+            NodeSelection::DepthFirst => open.pop(),
+            NodeSelection::BestBound => {
+                // Nodes without a bound yet come first.
+                let best = (0..open.len()).reduce(|best, i| {
+                    match (&open[best].bound, &open[i].bound) {
+                        (None, _) => best,
+                        (Some(_), None) => i,
+                        (Some(a), Some(b)) if maximized(b) > maximized(a) => i,
+                        _ => best,
+                    }
+                })?;
+                Some(open.remove(best))
+            }
+        }
+    }
+
+    /// Integer variable to branch on at the point `values`, as the configured
+    /// [`BranchingRule`] says, or `None` if every one is integer already.
+    fn branching_variable(&self, values: &[(String, T)]) -> Option<usize> {
+        // Distance of each fractional value to the nearest integer.
+        let fractional = self
+            .get_variable_kinds()
+            .into_iter()
+            .zip(values.iter())
+            .enumerate()
+            .filter(|(_, (kind, (_, v)))| kind.is_integer() && !v.is_integer())
+            .map(|(j, (_, (_, v)))| {
+                let fraction = v.clone() - v.floor();
+                let distance = if fraction > T::one() - fraction.clone() {
+                    T::one() - fraction
+                } else {
+                    fraction
+                };
+                (j, distance)
+            });
+
+        match self.get_branching_rule() {
+            BranchingRule::FirstFractional => fractional.map(|(j, _)| j).next(),
+            BranchingRule::MostFractional => fractional
+                .reduce(|best, c| if c.1 > best.1 { c } else { best })
+                .map(|(j, _)| j),
+            BranchingRule::LeastFractional => fractional
+                .reduce(|best, c| if c.1 < best.1 { c } else { best })
+                .map(|(j, _)| j),
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::{
+        rational::Rational,
+        simplex::{Relation, VariableKind, tests::problem},
+    };
+
+    /// Largest value of every variable of an [`IntegerProblem`].
+    const UPPER: i64 = 4;
+
+    /// A pure integer problem over `0 ≤ x ≤ UPPER`, small enough to solve by
+    /// trying every point.
+    pub(crate) struct IntegerProblem {
+        pub(crate) simplex: Simplex<Rational>,
+        rows: Vec<(Vec<i64>, Relation, i64)>,
+        target: Vec<i64>,
+    }
+
+    impl IntegerProblem {
+        /// Every integer point that satisfies the constraints.
+        pub(crate) fn feasible_points(&self) -> Vec<Vec<i64>> {
+            let mut points = vec![vec![]];
+            for _ in 0..self.target.len() {
+                points = points
+                    .into_iter()
+                    .flat_map(|point: Vec<i64>| {
+                        (0..=UPPER).map(move |x| [point.clone(), vec![x]].concat())
+                    })
+                    .collect();
+            }
+            points.retain(|point| {
+                self.rows.iter().all(|(row, relation, rhs)| {
+                    let lhs: i64 = row.iter().zip(point).map(|(a, x)| a * x).sum();
+                    match relation {
+                        Relation::LessEqual => lhs <= *rhs,
+                        Relation::GreaterEqual => lhs >= *rhs,
+                        Relation::Equal => lhs == *rhs,
+                    }
+                })
+            });
+            points
+        }
+
+        /// Best objective value over [`IntegerProblem::feasible_points`].
+        pub(crate) fn brute_force(&self) -> Option<Rational> {
+            let values = self.feasible_points().into_iter().map(|point| {
+                self.target
+                    .iter()
+                    .zip(point.iter())
+                    .map(|(c, x)| c * x)
+                    .sum::<i64>()
+            });
+            match self.simplex.get_objective_sense() {
+                ObjectiveSense::Maximize => values.max(),
+                ObjectiveSense::Minimize => values.min(),
+            }
+            .map(Rational::from)
+        }
+    }
+
+    /// Problems in two or three variables with coefficients in `-5..=5`
+    /// from a fixed pseudo-random sequence.
+    pub(crate) fn integer_problems(count: usize) -> Vec<IntegerProblem> {
+        let mut state = 17_u64;
+        let mut next = move || {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (state >> 33) as i64 % 11 - 5
+        };
+        (0..count)
+            .map(|case| {
+                let n = 2 + case % 2;
+                let rows: Vec<(Vec<i64>, Relation, i64)> = (0..1 + case % 3)
+                    .map(|i| {
+                        let row = (0..n).map(|_| next()).collect();
+                        let relation = match (i + case) % 4 {
+                            0 | 2 => Relation::LessEqual,
+                            1 => Relation::GreaterEqual,
+                            _ => Relation::Equal,
+                        };
+                        (row, relation, 2 * next() + 3)
+                    })
+                    .collect();
+                let target: Vec<i64> = (0..n).map(|_| next()).collect();
+
+                let slices: Vec<(&[i64], Relation, i64)> = rows
+                    .iter()
+                    .map(|(row, relation, rhs)| (row.as_slice(), *relation, *rhs))
+                    .collect();
+                let mut simplex = problem(&slices, &target);
+                for j in 0..n {
+                    simplex.set_variable_kind(j, VariableKind::Integer);
+                    simplex.set_bounds(
+                        j,
+                        Bounds {
+                            lower: Some(0.into()),
+                            upper: Some(UPPER.into()),
+                        },
+                    );
+                }
+                if case % 2 == 1 {
+                    simplex.set_objective_sense(ObjectiveSense::Minimize);
+                }
+                IntegerProblem {
+                    simplex,
+                    rows,
+                    target,
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn matches_brute_force() {
+        for mut problem in integer_problems(100) {
+            let best = problem.brute_force();
+            for node_selection in [NodeSelection::DepthFirst, NodeSelection::BestBound] {
+                for branching_rule in BranchingRule::ALL {
+                    problem.simplex.set_node_selection(node_selection);
+                    problem.simplex.set_branching_rule(branching_rule);
+                    let result = problem.simplex.run_branch_and_bound();
+                    let context = format!(
+                        "{} {}\n{}\n{:?}",
+                        node_selection, branching_rule, problem.simplex, result
+                    );
+                    match &best {
+                        Some(best) => {
+                            assert_eq!(result.status, BranchStatus::Optimal, "{}", context);
+                            let incumbent = result.incumbent.as_ref().unwrap();
+                            assert_eq!(incumbent.objective, *best, "{}", context);
+                            assert!(
+                                incumbent.values.iter().all(|(_, v)| v.is_integer()),
+                                "{}",
+                                context
+                            );
+                        }
+                        None => assert_eq!(result.status, BranchStatus::Infeasible, "{}", context),
+                    }
+                }
+            }
+        }
+    }
+}
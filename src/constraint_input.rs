@@ -1,10 +1,14 @@
 use web_sys::{HtmlInputElement, wasm_bindgen::JsCast};
-use yew::{Callback, Event, Html, InputEvent, Properties, function_component, html};
+use yew::{Callback, Event, Html, InputEvent, MouseEvent, Properties, function_component, html};
+
+use crate::simplex::Relation;
 
 #[derive(Clone, PartialEq, Properties)]
 pub struct Props {
     pub variables: Vec<String>,
+    pub relation: Relation,
     pub on_change_constraint: Callback<(String, usize)>,
+    pub on_change_relation: Callback<MouseEvent>,
 }
 
 fn get_value_from_input_event(e: InputEvent) -> String {
@@ -19,7 +23,9 @@ fn get_value_from_input_event(e: InputEvent) -> String {
 pub fn variable_input(properties: &Props) -> Html {
     let Props {
         variables,
+        relation,
         on_change_constraint,
+        on_change_relation,
     } = properties.clone();
 
     let oninputs: Vec<Callback<InputEvent>> = (0..variables.len() + 1)
@@ -46,7 +52,7 @@ pub fn variable_input(properties: &Props) -> Html {
                 })
             }
             <strong>
-            {" ≤ "}
+            <button onclick={on_change_relation} title="Change relation">{relation.to_string()}</button>
             <input type="text" oninput={oninputs.last().unwrap().clone()}/>
             </strong>
         </>
@@ -0,0 +1,275 @@
+use std::fmt;
+
+use crate::{
+    scalar::Scalar,
+    simplex::{Bounds, Relation, Simplex, SolveResult, Tableau, VariableKind, write_linear},
+};
+
+/// Why [`Simplex::run_gomory`] cannot cut a problem.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GomoryError {
+    /// Some variable is not restricted to integer values.
+    NotPureInteger,
+    /// Some variable has a lower bound other than zero, or an upper bound
+    /// that is not an integer.
+    UnsupportedBounds,
+    /// Some constraint has a coefficient or right-hand side that is not an
+    /// integer, so its slack need not be an integer either.
+    FractionalData,
+}
+
+impl fmt::Display for GomoryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GomoryError::NotPureInteger => write!(f, "every variable must be integer"),
+            GomoryError::UnsupportedBounds => {
+                write!(
+                    f,
+                    "every variable must be x ≥ 0, with an integer upper bound if any"
+                )
+            }
+            GomoryError::FractionalData => {
+                write!(f, "every constraint must have integer coefficients")
+            }
+        }
+    }
+}
+
+impl std::error::Error for GomoryError {}
+
+/// A Gomory fractional cut, with the tableau row it was derived from.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GomoryCut<T> {
+    /// Basic variable of the source row.
+    pub source: String,
+    /// Non-zero entries of the source row `x_B + Σ a_j x_j = b`, by column.
+    pub row: Vec<(String, T)>,
+    pub rhs: T,
+    /// The cut `Σ f_j x_j ≥ f_0` over the non-basic columns, where `f_j` and
+    /// `f_0` are the fractional parts of `a_j` and `b`.
+    pub cut: Vec<(String, T)>,
+    pub cut_rhs: T,
+    /// The same cut over the decision variables, with every slack replaced
+    /// by its definition, as it is added to the problem.
+    pub constraint: Vec<(String, T)>,
+    pub constraint_rhs: T,
+}
+
+impl<T: Scalar> fmt::Display for GomoryCut<T> {
+    /// Writes the derivation over three lines: the source row, the cut over
+    /// its columns and the cut over the decision variables.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let linear = |f: &mut fmt::Formatter<'_>, terms: &[(String, T)]| {
+            let (variables, coefficients): (Vec<String>, Vec<T>) = terms.iter().cloned().unzip();
+            write_linear(f, &coefficients, &variables)
+        };
+        write!(f, "Row of {}: ", self.source)?;
+        linear(f, &self.row)?;
+        writeln!(f, " = {}", self.rhs)?;
+        write!(f, "Cut: ")?;
+        linear(f, &self.cut)?;
+        writeln!(f, " ≥ {}", self.cut_rhs)?;
+        write!(f, "In the variables: ")?;
+        linear(f, &self.constraint)?;
+        write!(f, " ≥ {}", self.constraint_rhs)
+    }
+}
+
+/// Outcome of [`Simplex::run_gomory`].
+#[derive(Debug, Clone)]
+pub struct CuttingPlanes<T> {
+    /// Every cut added, in order.
+    pub cuts: Vec<GomoryCut<T>>,
+    /// The problem with upper bounds written as constraints and every cut
+    /// appended as a `≥` constraint.
+    pub problem: Simplex<T>,
+    /// Solution of `problem`, integer unless the cut limit was reached first.
+    pub result: SolveResult<T>,
+}
+
+impl<T: Scalar> Simplex<T> {
+    /// Solves a pure integer problem with Gomory's fractional cutting-plane
+    /// method, adding at most `max_cuts` cuts.
+    ///
+    /// While the optimal tableau has a row with a fractional right-hand side,
+    /// the one whose fractional part is closest to `1/2` yields a cut every
+    /// integer point satisfies but the current vertex does not. The cut is
+    /// appended as a new constraint and the problem solved again.
+    pub fn run_gomory(&self, max_cuts: usize) -> Result<CuttingPlanes<T>, GomoryError> {
+        if !self.get_variable_kinds().iter().all(|k| k.is_integer()) {
+            return Err(GomoryError::NotPureInteger);
+        }
+        let n = self.get_variables().len();
+        let data_integer = self
+            .get_constraints()
+            .iter()
+            .all(|row| row.iter().all(|a| a.is_integer()));
+        if !data_integer {
+            return Err(GomoryError::FractionalData);
+        }
+
+        // Upper bounds, binary ones included, become rows, so every slack is
+        // a column of the tableau.
+        let bounded = self.with_binary_bounds().unwrap_or_else(|| self.clone());
+        let mut problem = bounded.clone();
+        for (j, bounds) in bounded.get_bounds().into_iter().enumerate() {
+            if !bounds.lower.as_ref().is_some_and(|l| l.is_zero()) {
+                return Err(GomoryError::UnsupportedBounds);
+            }
+            if let Some(upper) = bounds.upper {
+                if !upper.is_integer() {
+                    return Err(GomoryError::UnsupportedBounds);
+                }
+                let mut row = vec![T::zero(); n + 1];
+                row[j] = T::one();
+                row[n] = upper;
+                problem.set_bounds(j, Bounds::default());
+                problem.set_variable_kind(j, VariableKind::Integer);
+                problem.add_constraint(row, Relation::LessEqual);
+            }
+        }
+
+        let mut cuts = vec![];
+        while cuts.len() < max_cuts {
+            let Some(tableau) = problem.optimal_tableau() else {
+                break;
+            };
+            let Some(cut) = problem.gomory_cut(&tableau) else {
+                break;
+            };
+            let mut constraint: Vec<T> = cut.constraint.iter().map(|(_, a)| a.clone()).collect();
+            constraint.push(cut.constraint_rhs.clone());
+            problem.add_constraint(constraint, Relation::GreaterEqual);
+            cuts.push(cut);
+        }
+
+        let result = problem.run_simplex();
+        Ok(CuttingPlanes {
+            cuts,
+            problem,
+            result,
+        })
+    }
+
+    /// Cut from the row of the optimal `tableau` whose right-hand side has
+    /// the fractional part closest to `1/2`, or `None` if every one is an
+    /// integer.
+    fn gomory_cut(&self, tableau: &Tableau<T>) -> Option<GomoryCut<T>> {
+        let variables = self.get_variables();
+        let n = variables.len();
+        let width = tableau.columns.len();
+        let fraction = |v: &T| v.clone() - v.floor();
+        let distance = |v: &T| {
+            let f = fraction(v);
+            if f > T::one() - f.clone() {
+                T::one() - f
+            } else {
+                f
+            }
+        };
+
+        let k = (0..tableau.basis.len())
+            .filter(|&i| !tableau.matrix[i][width].is_integer())
+            .reduce(|best, i| {
+                if distance(&tableau.matrix[i][width]) > distance(&tableau.matrix[best][width]) {
+                    i
+                } else {
+                    best
+                }
+            })?;
+        let row = &tableau.matrix[k];
+        let cut: Vec<(usize, T)> = (0..width)
+            .filter(|j| !row[*j].is_integer())
+            .map(|j| (j, fraction(&row[j])))
+            .collect();
+        let cut_rhs = fraction(&row[width]);
+
+        // A slack `s` of a tableau row `a'x + σs = b'` is `σ(b' - a'x)`, with
+        // `σ = ±1`.
+        let initial = self.initial_tableau();
+        let last = initial.matrix[0].len() - 1;
+        let mut constraint = vec![T::zero(); n];
+        let mut constraint_rhs = cut_rhs.clone();
+        for (j, f) in cut.iter() {
+            if *j < n {
+                constraint[*j] = constraint[*j].clone() + f.clone();
+                continue;
+            }
+            let defining = initial
+                .matrix
+                .iter()
+                .find(|r| !r[*j].is_zero())
+                .expect("slack column without a row");
+            let factor = f.clone() * defining[*j].clone();
+            for (c, a) in constraint.iter_mut().zip(defining.iter()) {
+                *c = c.clone() - factor.clone() * a.clone();
+            }
+            constraint_rhs = constraint_rhs - factor * defining[last].clone();
+        }
+
+        let named = |entries: Vec<(usize, T)>| -> Vec<(String, T)> {
+            entries
+                .into_iter()
+                .map(|(j, a)| (tableau.columns[j].clone(), a))
+                .collect()
+        };
+        Some(GomoryCut {
+            source: tableau.columns[tableau.basis[k]].clone(),
+            row: named(
+                (0..width)
+                    .filter(|j| !row[*j].is_zero())
+                    .map(|j| (j, row[j].clone()))
+                    .collect(),
+            ),
+            rhs: row[width].clone(),
+            cut: named(cut),
+            cut_rhs,
+            constraint: variables.into_iter().zip(constraint).collect(),
+            constraint_rhs,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        branch_and_bound::tests::integer_problems, rational::Rational, scalar::Scalar,
+        simplex::SolveResult,
+    };
+
+    #[test]
+    fn matches_brute_force() {
+        for problem in integer_problems(100) {
+            let planes = problem.simplex.run_gomory(100).unwrap();
+            let context = format!("{}\n{:?}", problem.simplex, planes.result);
+
+            // No cut may remove an integer point.
+            let points = problem.feasible_points();
+            for cut in planes.cuts.iter() {
+                for point in points.iter() {
+                    let lhs = cut
+                        .constraint
+                        .iter()
+                        .zip(point.iter())
+                        .fold(Rational::from(0), |sum, ((_, a), &x)| {
+                            sum + a.clone() * x.into()
+                        });
+                    assert!(lhs >= cut.constraint_rhs, "{}\ncuts off {:?}", cut, point);
+                }
+            }
+
+            match (problem.brute_force(), &planes.result) {
+                (
+                    Some(best),
+                    SolveResult::Optimal {
+                        objective, values, ..
+                    },
+                ) if values.iter().all(|(_, v)| v.is_integer()) => {
+                    assert_eq!(*objective, best, "{}", context);
+                }
+                (None, SolveResult::Infeasible { .. }) => {}
+                _ => panic!("{}", context),
+            }
+        }
+    }
+}
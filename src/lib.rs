@@ -1,5 +1,20 @@
 pub mod app;
+pub mod big_m;
+pub mod bound_input;
+pub mod branch_and_bound;
 pub mod constraint_input;
+pub mod gomory;
+pub mod log;
+pub mod optimal_face;
+pub mod pricing;
+pub mod ranging;
+pub mod rational;
+pub mod revised;
+pub mod scalar;
 pub mod simplex;
+pub mod sparse;
+pub mod stepper;
+pub mod tableau_view;
 pub mod target_input;
+pub mod trace;
 pub mod variable_input;
@@ -0,0 +1,8 @@
+/// Writes `message` to the browser console. Off the web there is no console,
+/// so this does nothing there, which lets the solver run in native tests.
+pub fn log(message: &str) {
+    #[cfg(target_arch = "wasm32")]
+    web_sys::console::log_1(&message.into());
+    #[cfg(not(target_arch = "wasm32"))]
+    let _ = message;
+}
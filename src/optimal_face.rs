@@ -0,0 +1,306 @@
+use std::{
+    collections::{HashSet, VecDeque},
+    fmt,
+};
+
+use crate::{
+    scalar::Scalar,
+    simplex::{Simplex, get_new_matrix},
+};
+
+/// Every optimal solution of a problem: the face of the feasible region
+/// spanned by its optimal vertices and, if it is unbounded, its rays.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OptimalFace<T> {
+    pub objective: T,
+    /// Distinct optimal vertices, the first being the one the simplex
+    /// method stops at.
+    pub vertices: Vec<Vec<(String, T)>>,
+    /// Directions the objective stays optimal along without limit.
+    pub rays: Vec<Vec<(String, T)>>,
+    /// Whether every optimal basis was visited, rather than stopping at the
+    /// vertex or pivot limit.
+    pub complete: bool,
+}
+
+impl<T> OptimalFace<T> {
+    pub fn is_unique(&self) -> bool {
+        self.vertices.len() == 1 && self.rays.is_empty()
+    }
+}
+
+impl<T: Scalar> fmt::Display for OptimalFace<T> {
+    /// Writes the face as a convex combination of its vertices, plus a
+    /// non-negative combination of its rays.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let point = |f: &mut fmt::Formatter<'_>, values: &[(String, T)]| {
+            write!(f, "(")?;
+            for (i, (variable, value)) in values.iter().enumerate() {
+                if i > 0 {
+                    write!(f, ", ")?;
+                }
+                write!(f, "{} = {}", variable, value)?;
+            }
+            write!(f, ")")
+        };
+
+        if self.is_unique() {
+            write!(f, "Unique optimum at ")?;
+            return point(f, &self.vertices[0]);
+        }
+        write!(f, "Every optimal solution is ")?;
+        for (i, vertex) in self.vertices.iter().enumerate() {
+            if i > 0 {
+                write!(f, " + ")?;
+            }
+            write!(f, "λ{}·", i + 1)?;
+            point(f, vertex)?;
+        }
+        for (i, ray) in self.rays.iter().enumerate() {
+            write!(f, " + μ{}·", i + 1)?;
+            point(f, ray)?;
+        }
+        let lambdas: Vec<String> = (1..=self.vertices.len())
+            .map(|i| format!("λ{}", i))
+            .collect();
+        write!(f, " with {} = 1", lambdas.join(" + "))?;
+        if self.rays.is_empty() {
+            write!(f, " and λ ≥ 0")?;
+        } else {
+            write!(f, " and λ, μ ≥ 0")?;
+        }
+        if !self.complete {
+            write!(f, ", among others not enumerated")?;
+        }
+        Ok(())
+    }
+}
+
+impl<T: Scalar> Simplex<T> {
+    /// Enumerates the optimal vertices of the problem, at most
+    /// `max_vertices` of them, or returns `None` if it has no optimal
+    /// solution.
+    ///
+    /// Starting from the optimal tableau of the two-phase method, every
+    /// non-basic column with a zero reduced cost is pivoted in, at every row
+    /// the ratio test allows, which moves to another optimal basis without
+    /// changing the objective value. A column with no row to pivot on is a
+    /// ray of the optimal face instead. At most
+    /// [`Simplex::get_max_iterations`] pivots are made.
+    ///
+    /// A free variable is split in two columns, so the points listed for it
+    /// may include ones between the actual vertices, which still describe
+    /// the same face.
+    pub fn optimal_face(&self, max_vertices: usize) -> Option<OptimalFace<T>> {
+        if let Some(bounded) = self.with_binary_bounds() {
+            return bounded.optimal_face(max_vertices);
+        }
+        let variables = self.get_variables();
+        let n = variables.len();
+        let named = |values: Vec<T>| -> Vec<(String, T)> {
+            variables.iter().cloned().zip(values).collect()
+        };
+
+        let bounds = self.get_bounds();
+        if !bounds
+            .iter()
+            .all(|b| b.lower.as_ref().is_some_and(|l| l.is_zero()))
+        {
+            let form = self.standard_form().ok()?;
+            let face = form.problem.optimal_face(max_vertices)?;
+            let columns = |values: Vec<(String, T)>| -> Vec<T> {
+                values.into_iter().map(|(_, v)| v).collect()
+            };
+            // The two columns of a free variable moving together leave it
+            // unchanged, so some rays vanish.
+            let mut rays: Vec<Vec<T>> = vec![];
+            for ray in face.rays {
+                let direction = normalized(form.direction(&columns(ray)));
+                if direction.iter().any(|d| !d.is_zero())
+                    && !rays.iter().any(|r| same(r, &direction))
+                {
+                    rays.push(direction);
+                }
+            }
+            return Some(OptimalFace {
+                objective: face.objective + form.offset.clone(),
+                vertices: face
+                    .vertices
+                    .into_iter()
+                    .map(|v| named(form.point(&columns(v))))
+                    .collect(),
+                rays: rays.into_iter().map(&named).collect(),
+                complete: face.complete,
+            });
+        }
+
+        let rows = self.with_upper_bound_rows();
+        let tableau = rows.optimal_tableau()?;
+        let width = tableau.columns.len();
+        let last = tableau.matrix.len() - 1;
+
+        let mut vertices: Vec<Vec<T>> = vec![];
+        let mut rays: Vec<Vec<T>> = vec![];
+        let mut complete = true;
+        let mut pivots = 0;
+        let mut seen = HashSet::from([sorted(&tableau.basis)]);
+        let mut open = VecDeque::from([(tableau.matrix, tableau.basis)]);
+        while let Some((matrix, basis)) = open.pop_front() {
+            let mut vertex = vec![T::zero(); n];
+            for (i, &b) in basis.iter().enumerate() {
+                if b < n {
+                    vertex[b] = matrix[i][width].clone();
+                }
+            }
+            if !vertices.iter().any(|v| same(v, &vertex)) {
+                if vertices.len() == max_vertices {
+                    complete = false;
+                    break;
+                }
+                vertices.push(vertex);
+            }
+
+            let entering = (0..width).filter(|j| !basis.contains(j) && matrix[last][*j].is_zero());
+            for j in entering {
+                let ratios: Vec<(usize, T)> = (0..basis.len())
+                    .filter(|&i| matrix[i][j].is_positive())
+                    .map(|i| (i, matrix[i][width].clone() / matrix[i][j].clone()))
+                    .collect();
+                let Some(min) = ratios
+                    .iter()
+                    .map(|(_, r)| r.clone())
+                    .reduce(|a, b| if b < a { b } else { a })
+                else {
+                    let ray = ray(&matrix, &basis, j, n);
+                    if ray.iter().any(|d| !d.is_zero()) && !rays.iter().any(|r| same(r, &ray)) {
+                        rays.push(ray);
+                    }
+                    continue;
+                };
+                for (i, ratio) in ratios {
+                    if (ratio - min.clone()).is_positive() {
+                        continue;
+                    }
+                    let mut next = basis.clone();
+                    next[i] = j;
+                    if !seen.insert(sorted(&next)) {
+                        continue;
+                    }
+                    if pivots == self.get_max_iterations() {
+                        complete = false;
+                        continue;
+                    }
+                    pivots += 1;
+                    open.push_back((get_new_matrix(matrix.clone(), j, i), next));
+                }
+            }
+        }
+
+        let target = self.get_target();
+        let objective = vertices[0]
+            .iter()
+            .zip(target.iter())
+            .fold(T::zero(), |sum, (x, c)| sum + x.clone() * c.clone());
+        Some(OptimalFace {
+            objective,
+            vertices: vertices.into_iter().map(&named).collect(),
+            rays: rays.into_iter().map(&named).collect(),
+            complete,
+        })
+    }
+}
+
+/// Change of the decision variables as the non-basic column `j` increases.
+fn ray<T: Scalar>(matrix: &[Vec<T>], basis: &[usize], j: usize, n: usize) -> Vec<T> {
+    let mut direction = vec![T::zero(); n];
+    if j < n {
+        direction[j] = T::one();
+    }
+    for (i, &b) in basis.iter().enumerate() {
+        if b < n {
+            direction[b] = -matrix[i][j].clone();
+        }
+    }
+    normalized(direction)
+}
+
+/// `direction` scaled so its largest entry is `1`, so rays compare equal
+/// whatever their length.
+fn normalized<T: Scalar>(direction: Vec<T>) -> Vec<T> {
+    let scale = direction
+        .iter()
+        .map(|d| d.abs())
+        .fold(T::zero(), |a, b| if b > a { b } else { a });
+    if scale.is_zero() {
+        return direction;
+    }
+    direction.into_iter().map(|d| d / scale.clone()).collect()
+}
+
+fn sorted(basis: &[usize]) -> Vec<usize> {
+    let mut basis = basis.to_vec();
+    basis.sort_unstable();
+    basis
+}
+
+fn same<T: Scalar>(a: &[T], b: &[T]) -> bool {
+    a.iter()
+        .zip(b.iter())
+        .all(|(x, y)| (x.clone() - y.clone()).is_zero())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{rational::Rational, simplex::Relation, simplex::tests::problem};
+
+    #[test]
+    fn degenerate_vertices_are_listed_once() {
+        // Both ends of the optimal edge x1 + x2 = 2 are degenerate: three
+        // constraints meet at each of them.
+        let simplex = problem::<Rational>(
+            &[
+                (&[1, 1], Relation::LessEqual, 2),
+                (&[1, 0], Relation::LessEqual, 2),
+                (&[0, 1], Relation::LessEqual, 2),
+                (&[1, -1], Relation::LessEqual, 2),
+            ],
+            &[1, 1],
+        );
+        let face = simplex.optimal_face(10).unwrap();
+        assert_eq!(face.objective, Rational::from(2));
+        assert!(face.complete);
+        assert!(face.rays.is_empty());
+
+        let mut vertices: Vec<Vec<Rational>> = face
+            .vertices
+            .iter()
+            .map(|vertex| vertex.iter().map(|(_, v)| v.clone()).collect())
+            .collect();
+        vertices.sort_by_key(|vertex| vertex[0].to_string());
+        let point = |x1: i64, x2: i64| vec![Rational::from(x1), Rational::from(x2)];
+        assert_eq!(vertices, [point(0, 2), point(2, 0)]);
+    }
+
+    #[test]
+    fn degenerate_simplex_face() {
+        // Every unit vector is optimal, and each lies on four constraints.
+        let simplex = problem::<Rational>(
+            &[
+                (&[1, 1, 1], Relation::LessEqual, 1),
+                (&[1, 0, 0], Relation::LessEqual, 1),
+                (&[0, 1, 0], Relation::LessEqual, 1),
+                (&[0, 0, 1], Relation::LessEqual, 1),
+            ],
+            &[1, 1, 1],
+        );
+        let face = simplex.optimal_face(10).unwrap();
+        assert!(face.complete);
+        let mut vertices: Vec<String> = face
+            .vertices
+            .iter()
+            .map(|vertex| vertex.iter().map(|(_, v)| v.to_string()).collect())
+            .collect();
+        vertices.sort();
+        assert_eq!(vertices, ["001", "010", "100"]);
+    }
+}
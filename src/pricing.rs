@@ -0,0 +1,300 @@
+use std::fmt;
+
+use crate::scalar::Scalar;
+
+/// Rule choosing the entering column among those with a negative reduced cost.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum PricingRule {
+    /// Most negative reduced cost.
+    #[default]
+    Dantzig,
+    /// Most negative reduced cost per unit length of the edge the pivot moves
+    /// along, `d_j / ‖(1, a_j)‖`.
+    SteepestEdge,
+    /// Steepest edge measured against a reference framework, with weights
+    /// updated cheaply from the pivot row after every pivot.
+    Devex,
+    /// Largest objective change over the whole step, `d_j · θ_j`, where `θ_j`
+    /// is the step length given by the ratio test, bounds included.
+    GreatestImprovement,
+    /// Dantzig's rule over one block of columns at a time, moving on to the
+    /// next block only when the current one has no candidate left.
+    Partial,
+}
+
+impl PricingRule {
+    pub const ALL: [PricingRule; 5] = [
+        PricingRule::Dantzig,
+        PricingRule::SteepestEdge,
+        PricingRule::Devex,
+        PricingRule::GreatestImprovement,
+        PricingRule::Partial,
+    ];
+
+    /// Next rule in [`PricingRule::ALL`], used by the UI toggle.
+    pub fn next(self) -> Self {
+        let i = Self::ALL.iter().position(|r| *r == self).unwrap();
+        Self::ALL[(i + 1) % Self::ALL.len()]
+    }
+
+    pub(crate) fn pricer<T: Scalar>(self) -> Box<dyn Pricer<T>> {
+        match self {
+            PricingRule::Dantzig => Box::new(Dantzig),
+            PricingRule::SteepestEdge => Box::new(SteepestEdge),
+            PricingRule::Devex => Box::new(Devex::default()),
+            PricingRule::GreatestImprovement => Box::new(GreatestImprovement),
+            PricingRule::Partial => Box::new(Partial::default()),
+        }
+    }
+}
+
+impl fmt::Display for PricingRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PricingRule::Dantzig => write!(f, "Dantzig"),
+            PricingRule::SteepestEdge => write!(f, "Steepest edge"),
+            PricingRule::Devex => write!(f, "Devex"),
+            PricingRule::GreatestImprovement => write!(f, "Greatest improvement"),
+            PricingRule::Partial => write!(f, "Partial"),
+        }
+    }
+}
+
+/// The edge of the feasible region each non-basic column moves the current
+/// vertex along when it enters, as far as a pricing rule needs to know it.
+pub(crate) trait Edges<T> {
+    /// `‖B⁻¹a_j‖²`, the squared length of the change of the basic variables
+    /// per unit step of column `j`.
+    fn squared_length(&self, j: usize) -> f64;
+
+    /// Longest step column `j` can take before a basic variable or its own
+    /// bound stops it, or `None` if nothing does.
+    fn step(&self, j: usize) -> Option<T>;
+}
+
+/// State of a pricing rule over one run of pivots.
+pub(crate) trait Pricer<T: Scalar> {
+    /// Column to enter the basis, given the reduced cost of every column,
+    /// negative where entering it improves the objective. `None` if no
+    /// reduced cost is negative.
+    fn select(&mut self, reduced: &[T], edges: &dyn Edges<T>) -> Option<usize>;
+
+    /// Called before `entering` replaces `leaving` in the basis, with the
+    /// entry of each column in the pivot row of `B⁻¹A`.
+    fn pivoted(&mut self, _pivot_row: &dyn Fn(usize) -> T, _entering: usize, _leaving: usize) {}
+}
+
+/// Candidate with the most negative `score`, ties going to the lowest column.
+fn best_by_score<T: Scalar>(candidates: impl Iterator<Item = (usize, T)>) -> Option<usize> {
+    let mut best: Option<(usize, T)> = None;
+    for (j, score) in candidates {
+        if best
+            .as_ref()
+            .is_none_or(|(_, b)| (score.clone() - b.clone()).is_negative())
+        {
+            best = Some((j, score));
+        }
+    }
+    best.map(|(j, _)| j)
+}
+
+/// Columns with a negative reduced cost, together with that cost.
+fn candidates<T: Scalar>(reduced: &[T]) -> impl Iterator<Item = (usize, T)> + '_ {
+    reduced
+        .iter()
+        .cloned()
+        .enumerate()
+        .filter(|(_, d)| d.is_negative())
+}
+
+/// Column with the most negative reduced cost, as Dantzig's rule picks it.
+/// The rule needs no edges, so it can price a tableau on its own.
+pub(crate) fn dantzig<T: Scalar>(reduced: &[T]) -> Option<usize> {
+    best_by_score(candidates(reduced))
+}
+
+struct Dantzig;
+
+impl<T: Scalar> Pricer<T> for Dantzig {
+    fn select(&mut self, reduced: &[T], _edges: &dyn Edges<T>) -> Option<usize> {
+        dantzig(reduced)
+    }
+}
+
+struct SteepestEdge;
+
+impl<T: Scalar> Pricer<T> for SteepestEdge {
+    fn select(&mut self, reduced: &[T], edges: &dyn Edges<T>) -> Option<usize> {
+        best_by_score(candidates(reduced).map(|(j, d)| {
+            let norm = 1_f64 + edges.squared_length(j);
+            (j, d / T::from_f64(norm.sqrt()))
+        }))
+    }
+}
+
+#[derive(Default)]
+struct Devex {
+    weights: Vec<f64>,
+}
+
+impl<T: Scalar> Pricer<T> for Devex {
+    fn select(&mut self, reduced: &[T], _edges: &dyn Edges<T>) -> Option<usize> {
+        // A new reference framework starts whenever the number of columns
+        // changes, as it does between the phases of the two-phase method.
+        if self.weights.len() != reduced.len() {
+            self.weights = vec![1_f64; reduced.len()];
+        }
+        best_by_score(
+            candidates(reduced).map(|(j, d)| (j, d / T::from_f64(self.weights[j].sqrt()))),
+        )
+    }
+
+    fn pivoted(&mut self, pivot_row: &dyn Fn(usize) -> T, entering: usize, leaving: usize) {
+        // No framework to update before the first selection, as when Bland's
+        // rule picks every column.
+        if self.weights.is_empty() {
+            return;
+        }
+        let pivot = pivot_row(entering).to_f64();
+        let entering_weight = self.weights[entering];
+
+        for (j, weight) in self.weights.iter_mut().enumerate() {
+            let ratio = pivot_row(j).to_f64() / pivot;
+            if j != entering && ratio != 0_f64 {
+                *weight = weight.max(ratio.powi(2) * entering_weight);
+            }
+        }
+        self.weights[leaving] = (entering_weight / pivot.powi(2)).max(1_f64);
+    }
+}
+
+struct GreatestImprovement;
+
+impl<T: Scalar> Pricer<T> for GreatestImprovement {
+    fn select(&mut self, reduced: &[T], edges: &dyn Edges<T>) -> Option<usize> {
+        let mut scores = vec![];
+        for (j, d) in candidates(reduced) {
+            // A column nothing limits improves the objective without bound.
+            let Some(step) = edges.step(j) else {
+                return Some(j);
+            };
+            scores.push((j, d.clone() * step, d));
+        }
+
+        // On a degenerate vertex every step is zero, so fall back to the
+        // reduced cost itself.
+        if scores.iter().all(|(_, s, _)| !s.is_negative()) {
+            return best_by_score(scores.into_iter().map(|(j, _, d)| (j, d)));
+        }
+        best_by_score(scores.into_iter().map(|(j, s, _)| (j, s)))
+    }
+}
+
+#[derive(Default)]
+struct Partial {
+    start: usize,
+}
+
+impl<T: Scalar> Pricer<T> for Partial {
+    fn select(&mut self, reduced: &[T], _edges: &dyn Edges<T>) -> Option<usize> {
+        let columns = reduced.len();
+        if columns == 0 {
+            return None;
+        }
+        let block = (columns as f64).sqrt().ceil().max(1_f64) as usize;
+
+        let mut start = self.start.min(columns - 1);
+        for _ in 0..columns.div_ceil(block) + 1 {
+            let end = (start + block).min(columns);
+            if let Some(j) = best_by_score(
+                (start..end)
+                    .map(|j| (j, reduced[j].clone()))
+                    .filter(|(_, d)| d.is_negative()),
+            ) {
+                self.start = start;
+                return Some(j);
+            }
+            start = if end == columns { 0 } else { end };
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Edges given outright, as a pricer would read them off a basis.
+    struct Fixed {
+        squared_lengths: Vec<f64>,
+        steps: Vec<Option<f64>>,
+    }
+
+    impl Edges<f64> for Fixed {
+        fn squared_length(&self, j: usize) -> f64 {
+            self.squared_lengths[j]
+        }
+
+        fn step(&self, j: usize) -> Option<f64> {
+            self.steps[j]
+        }
+    }
+
+    fn edges() -> Fixed {
+        Fixed {
+            squared_lengths: vec![8.0, 99.0, 0.0, 3.0],
+            steps: vec![Some(1.0), Some(1.0), Some(0.5), Some(4.0)],
+        }
+    }
+
+    fn select(rule: PricingRule, reduced: &[f64]) -> Option<usize> {
+        rule.pricer().select(reduced, &edges())
+    }
+
+    #[test]
+    fn each_rule_scores_its_own_way() {
+        let reduced = [-1.0, -4.0, 2.0, -2.0];
+        assert_eq!(select(PricingRule::Dantzig, &reduced), Some(1));
+        // -1 / 3 and -4 / 10 against -2 / 2.
+        assert_eq!(select(PricingRule::SteepestEdge, &reduced), Some(3));
+        assert_eq!(select(PricingRule::Devex, &reduced), Some(1));
+        // -4 · 1 against -2 · 4.
+        assert_eq!(select(PricingRule::GreatestImprovement, &reduced), Some(3));
+        assert_eq!(select(PricingRule::Dantzig, &[0.0, 1.0]), None);
+    }
+
+    #[test]
+    fn greatest_improvement_takes_an_unlimited_column() {
+        let mut edges = edges();
+        edges.steps[3] = None;
+        let mut pricer = PricingRule::GreatestImprovement.pricer();
+        assert_eq!(pricer.select(&[-1.0, -4.0, 2.0, -0.5], &edges), Some(3));
+    }
+
+    #[test]
+    fn partial_pricing_stays_in_its_block() {
+        let mut pricer = PricingRule::Partial.pricer();
+        // Blocks of two columns: the first has a candidate, however small.
+        let mut reduced = [0.0, -1.0, -5.0, 0.0];
+        assert_eq!(pricer.select(&reduced, &edges()), Some(1));
+        reduced[1] = 0.0;
+        assert_eq!(pricer.select(&reduced, &edges()), Some(2));
+        reduced[0] = -3.0;
+        // Starts from the block priced last, then goes round.
+        assert_eq!(pricer.select(&reduced, &edges()), Some(2));
+        reduced[2] = 0.0;
+        assert_eq!(pricer.select(&reduced, &edges()), Some(0));
+    }
+
+    #[test]
+    fn devex_weights_grow_with_the_pivot_row() {
+        let mut pricer = PricingRule::Devex.pricer();
+        let reduced = [-2.0, -1.0, 0.0];
+        assert_eq!(pricer.select(&reduced, &edges()), Some(0));
+        // Column 0 enters for column 2 with pivot row (1, 4, 0): column 1
+        // gets the weight 16.
+        let row = [1.0, 4.0, 0.0];
+        pricer.pivoted(&|j| row[j], 0, 2);
+        assert_eq!(pricer.select(&[-1.0, -3.0, -1.5], &edges()), Some(2));
+    }
+}
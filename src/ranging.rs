@@ -0,0 +1,345 @@
+use crate::{
+    revised::{Lu, dense, sparse},
+    scalar::Scalar,
+    sparse::CscMatrix,
+};
+
+/// A problem written as `Ax = b` with `0 ≤ x ≤ u` over the columns of a
+/// tableau, which the sensitivity analysis reads back from an optimal basis.
+pub(crate) struct Canonical<T> {
+    pub(crate) columns: CscMatrix<T>,
+    pub(crate) rhs: Vec<T>,
+    /// Cost of each column in the maximized target.
+    pub(crate) costs: Vec<T>,
+    pub(crate) upper: Vec<Option<T>>,
+    /// Columns from this one on are artificial and can never enter the basis.
+    pub(crate) eligible: usize,
+    /// Constraint each row was built from, and whether it was negated. Rows
+    /// for upper bounds belong to no constraint.
+    pub(crate) rows: Vec<Option<(usize, bool)>>,
+}
+
+/// What an optimal basis of a [`Canonical`] problem says about its
+/// constraints and costs, in terms of the maximized target. Limits of `None`
+/// are infinite.
+pub(crate) struct BasisAnalysis<T> {
+    /// Dual value of each constraint.
+    pub(crate) prices: Vec<T>,
+    /// How far the cost of each eligible column can increase and decrease
+    /// with the basis staying optimal.
+    pub(crate) cost_ranges: Vec<(Option<T>, Option<T>)>,
+    /// How far the right-hand side of each constraint can increase and
+    /// decrease with the basis staying feasible.
+    pub(crate) rhs_ranges: Vec<(Option<T>, Option<T>)>,
+    /// Whether some column that can move has a zero reduced cost.
+    pub(crate) alternative: bool,
+}
+
+impl<T: Scalar> Canonical<T> {
+    /// Analyzes `basis`, given as the basic column of each row, with the
+    /// non-basic columns flagged in `at_upper` sitting at their upper bound.
+    /// Returns `None` if the basis does not cover every row.
+    pub(crate) fn analyze(
+        &self,
+        basis: &[usize],
+        at_upper: &[bool],
+        constraints: usize,
+    ) -> Option<BasisAnalysis<T>> {
+        let m = self.rhs.len();
+        if basis.len() != m {
+            return None;
+        }
+        let lu = Lu::new(
+            &basis
+                .iter()
+                .map(|&j| {
+                    self.columns
+                        .column(j)
+                        .map(|(i, a)| (i, a.clone()))
+                        .collect()
+                })
+                .collect::<Vec<_>>(),
+        );
+
+        let mut row_of = vec![None; self.columns.columns()];
+        for (i, &b) in basis.iter().enumerate() {
+            row_of[b] = Some(i);
+        }
+        let at_upper = |j: usize| {
+            row_of[j].is_none()
+                && at_upper.get(j).copied().unwrap_or(false)
+                && self.upper[j].is_some()
+        };
+
+        let basic_costs = basis.iter().map(|&b| self.costs[b].clone()).enumerate();
+        let y = dense(lu.solve_transposed(basic_costs), m);
+        let reduced: Vec<T> = self
+            .costs
+            .iter()
+            .enumerate()
+            .map(|(j, c)| c.clone() - self.columns.dot_column(j, &y))
+            .collect();
+
+        let mut rhs = self.rhs.clone();
+        for j in (0..self.columns.columns()).filter(|&j| at_upper(j)) {
+            let u = self.upper[j].clone().unwrap();
+            for (i, a) in self.columns.column(j) {
+                rhs[i] = rhs[i].clone() - a.clone() * u.clone();
+            }
+        }
+        let basic_values = dense(lu.solve(sparse(&rhs)), m);
+
+        let mut prices = vec![T::zero(); constraints];
+        for (price, row) in y.iter().zip(self.rows.iter()) {
+            if let Some((i, negated)) = row {
+                prices[*i] = prices[*i].clone() + signed(price.clone(), *negated);
+            }
+        }
+
+        // Columns that can still move away from the bound they sit at.
+        let movable: Vec<usize> = (0..self.eligible)
+            .filter(|&l| row_of[l].is_none())
+            .filter(|&l| !matches!(&self.upper[l], Some(u) if u.is_zero()))
+            .collect();
+        let alternative = movable.iter().any(|&l| reduced[l].is_zero());
+        let mut is_movable = vec![false; self.columns.columns()];
+        for &l in movable.iter() {
+            is_movable[l] = true;
+        }
+        let by_row = self.columns.to_csr();
+        let mut alpha = vec![T::zero(); self.columns.columns()];
+        let cost_ranges = (0..self.eligible)
+            .map(|j| match row_of[j] {
+                // A non-basic column enters once its reduced cost changes sign.
+                None if at_upper(j) => (None, Some(clamp(reduced[j].clone()))),
+                None => (Some(clamp(-reduced[j].clone())), None),
+                // Changing the cost of a basic column shifts every reduced
+                // cost by a multiple of its row of `B⁻¹A`, which only has
+                // entries in the columns sharing a row with `eᵀB⁻¹`.
+                Some(k) => {
+                    let mut touched = vec![];
+                    for (i, rho) in lu.solve_transposed([(k, T::one())]) {
+                        for (l, a) in by_row.row(i).filter(|(l, _)| is_movable[*l]) {
+                            if alpha[l] == T::zero() {
+                                touched.push(l);
+                            }
+                            alpha[l] = alpha[l].clone() + rho.clone() * a.clone();
+                        }
+                    }
+                    let (mut increase, mut decrease) = (None, None);
+                    for l in touched {
+                        let alpha = std::mem::replace(&mut alpha[l], T::zero());
+                        if alpha.is_zero() {
+                            continue;
+                        }
+                        let ratio = (reduced[l].clone() / alpha.clone()).abs();
+                        if alpha.is_positive() == at_upper(l) {
+                            tighten(&mut increase, ratio);
+                        } else {
+                            tighten(&mut decrease, ratio);
+                        }
+                    }
+                    (increase, decrease)
+                }
+            })
+            .collect();
+
+        // Rows each constraint appears in, with the sign it appears with.
+        let mut directions = vec![vec![]; constraints];
+        for (r, row) in self.rows.iter().enumerate() {
+            if let Some((i, negated)) = row {
+                directions[*i].push((r, signed(T::one(), *negated)));
+            }
+        }
+        let rhs_ranges = directions
+            .into_iter()
+            .map(|direction| {
+                let (mut increase, mut decrease) = (None, None);
+                for (k, v) in lu.solve(direction) {
+                    if v.is_zero() {
+                        continue;
+                    }
+                    let (b, x) = (basis[k], &basic_values[k]);
+                    let room = self.upper[b].clone().map(|u| u - x.clone());
+                    let (towards_zero, towards_upper) = if v.is_positive() {
+                        (&mut decrease, &mut increase)
+                    } else {
+                        (&mut increase, &mut decrease)
+                    };
+                    let v = v.abs();
+                    tighten(towards_zero, clamp(x.clone() / v.clone()));
+                    if let Some(room) = room {
+                        tighten(towards_upper, clamp(room / v));
+                    }
+                }
+                (increase, decrease)
+            })
+            .collect();
+
+        Some(BasisAnalysis {
+            prices,
+            cost_ranges,
+            rhs_ranges,
+            alternative,
+        })
+    }
+}
+
+fn signed<T: Scalar>(value: T, negated: bool) -> T {
+    if negated { -value } else { value }
+}
+
+/// Rounds a limit that rounding noise pushed below zero back up to it.
+fn clamp<T: Scalar>(value: T) -> T {
+    if value.is_negative() {
+        T::zero()
+    } else {
+        value
+    }
+}
+
+/// Lowers `limit` to `value` if that is tighter, with `None` as infinity.
+pub(crate) fn tighten<T: Scalar>(limit: &mut Option<T>, value: T) {
+    if limit.as_ref().is_none_or(|l| value < *l) {
+        *limit = Some(value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        rational::Rational,
+        scalar::Scalar,
+        simplex::{
+            AllowableRange, ObjectiveSense, Relation, Sensitivity, Simplex, SolveResult,
+            VariableKind, tests::problem,
+        },
+    };
+
+    /// Problems with a unique and non-degenerate optimum, where the ranges of
+    /// the optimal basis are exactly where the solution stays the same.
+    fn problems() -> Vec<Simplex<Rational>> {
+        let maximize = problem(
+            &[
+                (&[1, 1], Relation::LessEqual, 4),
+                (&[1, 3], Relation::LessEqual, 9),
+                (&[1, 0], Relation::LessEqual, 3),
+            ],
+            &[3, 2],
+        );
+        let mut minimize = problem(
+            &[
+                (&[1, 1, 1], Relation::GreaterEqual, 6),
+                (&[2, 1, 0], Relation::GreaterEqual, 5),
+                (&[0, 1, 3], Relation::LessEqual, 12),
+            ],
+            &[4, 3, 5],
+        );
+        minimize.set_objective_sense(ObjectiveSense::Minimize);
+        vec![maximize, minimize]
+    }
+
+    /// The optimal objective value, the value of each variable and the
+    /// sensitivity report, or `None` if there is no optimum.
+    fn solve(
+        simplex: &Simplex<Rational>,
+    ) -> Option<(Rational, Vec<Rational>, Sensitivity<Rational>)> {
+        match simplex.run_simplex() {
+            SolveResult::Optimal {
+                objective,
+                values,
+                sensitivity,
+                ..
+            } => Some((
+                objective,
+                values.into_iter().map(|(_, v)| v).collect(),
+                sensitivity,
+            )),
+            _ => None,
+        }
+    }
+
+    /// Changes to try for a range: halfway to each end, or by one towards an
+    /// infinite one, which must keep the basis, and half a unit past each
+    /// finite end, which must not.
+    fn steps(range: &AllowableRange<Rational>) -> Vec<(Rational, bool)> {
+        let half = Rational::new(1, 2);
+        let inside =
+            |limit: &Option<Rational>| limit.clone().map_or(1.into(), |l| l * half.clone());
+        let past = |limit: &Option<Rational>| limit.clone().map(|l| l + half.clone());
+        [
+            (inside(&range.increase), true),
+            (-inside(&range.decrease), true),
+        ]
+        .into_iter()
+        .filter(|(step, _)| !step.is_zero())
+        .chain(past(&range.increase).map(|step| (step, false)))
+        .chain(past(&range.decrease).map(|step| (-step, false)))
+        .collect()
+    }
+
+    #[test]
+    fn cost_ranges_match_perturbation() {
+        for simplex in problems() {
+            let (_, values, sensitivity) = solve(&simplex).unwrap();
+            for (j, range) in sensitivity.objective_ranges.iter().enumerate() {
+                for (step, stays) in steps(range) {
+                    let mut perturbed = simplex.clone();
+                    let mut target = simplex.get_target();
+                    target[j] = target[j].clone() + step.clone();
+                    perturbed.set_target(target);
+                    let (_, moved, _) = solve(&perturbed).unwrap();
+                    assert_eq!(
+                        moved == values,
+                        stays,
+                        "cost of x{} by {}\n{}",
+                        j + 1,
+                        step,
+                        simplex
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn rhs_ranges_match_perturbation() {
+        for simplex in problems() {
+            let (objective, _, sensitivity) = solve(&simplex).unwrap();
+            for (i, range) in sensitivity.rhs_ranges.iter().enumerate() {
+                for (step, stays) in steps(range) {
+                    let mut perturbed = Simplex::default();
+                    for variable in simplex.get_variables() {
+                        perturbed.add_variable(variable, VariableKind::Continuous);
+                    }
+                    for (k, (mut row, relation)) in simplex
+                        .get_constraints()
+                        .into_iter()
+                        .zip(simplex.get_relations())
+                        .enumerate()
+                    {
+                        if k == i {
+                            *row.last_mut().unwrap() = row.last().unwrap().clone() + step.clone();
+                        }
+                        perturbed.add_constraint(row, relation);
+                    }
+                    perturbed.set_target(simplex.get_target());
+                    perturbed.set_objective_sense(simplex.get_objective_sense());
+                    // The shadow price holds exactly while the basis stays
+                    // feasible.
+                    let predicted =
+                        objective.clone() + sensitivity.shadow_prices[i].clone() * step.clone();
+                    let holds = solve(&perturbed).is_some_and(|(z, _, _)| z == predicted);
+                    assert_eq!(
+                        holds,
+                        stays,
+                        "rhs of row {} by {}\n{}",
+                        i + 1,
+                        step,
+                        simplex
+                    );
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,333 @@
+use std::{
+    cmp::Ordering,
+    fmt,
+    ops::{Add, Div, Mul, Neg, Sub},
+    str::FromStr,
+};
+
+use num_bigint::BigInt;
+use num_integer::Integer;
+use num_traits::{One, Signed, ToPrimitive, Zero};
+
+use crate::scalar::Scalar;
+
+/// Exact fraction of two arbitrary-precision integers, always kept in lowest
+/// terms with a positive denominator.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Rational {
+    numerator: BigInt,
+    denominator: BigInt,
+}
+
+/// Largest power of ten accepted when parsing, so a typo like `1e999999999`
+/// cannot stall the page building a huge integer.
+const MAX_EXPONENT: u32 = 1000;
+
+/// Error returned when a string is not a number like `3`, `-2.5`, `1e3` or
+/// `7/3`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseRationalError;
+
+impl Rational {
+    /// `numerator / denominator`, reduced to lowest terms.
+    ///
+    /// Panics if `denominator` is zero.
+    pub fn new(numerator: impl Into<BigInt>, denominator: impl Into<BigInt>) -> Self {
+        let numerator = numerator.into();
+        let denominator = denominator.into();
+        assert!(!denominator.is_zero(), "zero denominator");
+
+        let gcd = numerator.gcd(&denominator);
+        let (mut numerator, mut denominator) = (numerator / &gcd, denominator / &gcd);
+        if denominator.is_negative() {
+            numerator = -numerator;
+            denominator = -denominator;
+        }
+        Self {
+            numerator,
+            denominator,
+        }
+    }
+
+    pub fn numerator(&self) -> &BigInt {
+        &self.numerator
+    }
+
+    pub fn denominator(&self) -> &BigInt {
+        &self.denominator
+    }
+}
+
+impl Default for Rational {
+    fn default() -> Self {
+        Self::from(0)
+    }
+}
+
+impl From<i64> for Rational {
+    fn from(value: i64) -> Self {
+        Self {
+            numerator: value.into(),
+            denominator: BigInt::one(),
+        }
+    }
+}
+
+impl Scalar for Rational {
+    fn zero() -> Self {
+        Self::from(0)
+    }
+
+    fn one() -> Self {
+        Self::from(1)
+    }
+
+    /// Exact value of `value`, which as a binary float is always a fraction
+    /// with a power of two as denominator. Infinities and NaN map to zero.
+    fn from_f64(value: f64) -> Self {
+        if !value.is_finite() {
+            return Self::from(0);
+        }
+
+        let bits = value.to_bits();
+        let exponent = ((bits >> 52) & 0x7ff) as i32;
+        let mut mantissa = BigInt::from(bits & ((1_u64 << 52) - 1));
+        let exponent = if exponent == 0 {
+            -1074
+        } else {
+            mantissa += BigInt::one() << 52;
+            exponent - 1075
+        };
+        if bits >> 63 == 1 {
+            mantissa = -mantissa;
+        }
+
+        if exponent >= 0 {
+            Self::new(mantissa << exponent as usize, 1)
+        } else {
+            Self::new(mantissa, BigInt::one() << (-exponent) as usize)
+        }
+    }
+
+    fn to_f64(&self) -> f64 {
+        match (self.numerator.to_f64(), self.denominator.to_f64()) {
+            (Some(n), Some(d)) if n.is_finite() && d.is_finite() => n / d,
+            _ => {
+                // Scale both down so they fit in an f64.
+                let shift = self.denominator.bits().max(self.numerator.bits()) as usize - 1000;
+                let n = (&self.numerator >> shift).to_f64().unwrap_or(0_f64);
+                let d = (&self.denominator >> shift).to_f64().unwrap_or(1_f64);
+                n / d
+            }
+        }
+    }
+
+    fn is_negative(&self) -> bool {
+        self.numerator.is_negative()
+    }
+
+    fn is_positive(&self) -> bool {
+        self.numerator.is_positive()
+    }
+
+    fn floor(&self) -> Self {
+        Self::new(self.numerator.div_floor(&self.denominator), 1)
+    }
+
+    fn is_integer(&self) -> bool {
+        self.denominator.is_one()
+    }
+
+    fn abs(&self) -> Self {
+        Self {
+            numerator: self.numerator.abs(),
+            denominator: self.denominator.clone(),
+        }
+    }
+
+    fn is_zero(&self) -> bool {
+        self.numerator.is_zero()
+    }
+}
+
+impl Ord for Rational {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (&self.numerator * &other.denominator).cmp(&(&other.numerator * &self.denominator))
+    }
+}
+
+impl PartialOrd for Rational {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Add for Rational {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self::new(
+            self.numerator * &rhs.denominator + rhs.numerator * &self.denominator,
+            self.denominator * rhs.denominator,
+        )
+    }
+}
+
+impl Sub for Rational {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        self + -rhs
+    }
+}
+
+impl Mul for Rational {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        Self::new(
+            self.numerator * rhs.numerator,
+            self.denominator * rhs.denominator,
+        )
+    }
+}
+
+impl Div for Rational {
+    type Output = Self;
+
+    /// Panics if `rhs` is zero.
+    fn div(self, rhs: Self) -> Self {
+        Self::new(
+            self.numerator * rhs.denominator,
+            self.denominator * rhs.numerator,
+        )
+    }
+}
+
+impl Neg for Rational {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self {
+            numerator: -self.numerator,
+            denominator: self.denominator,
+        }
+    }
+}
+
+impl fmt::Display for Rational {
+    /// Prints integers as `3` and everything else as `7/3`. Any precision in
+    /// the format string is ignored, since the value is exact.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_integer() {
+            write!(f, "{}", self.numerator)
+        } else {
+            write!(f, "{}/{}", self.numerator, self.denominator)
+        }
+    }
+}
+
+impl FromStr for Rational {
+    type Err = ParseRationalError;
+
+    /// Parses integers, decimals with an optional exponent and fractions of
+    /// those, such as `3`, `-2.5`, `1e3` or `7/3`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some((numerator, denominator)) = s.split_once('/') {
+            let numerator: Rational = numerator.parse()?;
+            let denominator: Rational = denominator.parse()?;
+            if denominator.numerator.is_zero() {
+                return Err(ParseRationalError);
+            }
+            return Ok(numerator / denominator);
+        }
+
+        let s = s.trim();
+        let (mantissa, exponent) = match s.split_once(['e', 'E']) {
+            Some((mantissa, exponent)) => (
+                mantissa,
+                exponent.parse::<i32>().map_err(|_| ParseRationalError)?,
+            ),
+            None => (s, 0),
+        };
+        let (integer, fraction) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+        let (sign, integer) = match integer.strip_prefix('-') {
+            Some(integer) => (-1, integer),
+            None => (1, integer.strip_prefix('+').unwrap_or(integer)),
+        };
+        if integer.is_empty() && fraction.is_empty()
+            || !integer
+                .chars()
+                .chain(fraction.chars())
+                .all(|c| c.is_ascii_digit())
+        {
+            return Err(ParseRationalError);
+        }
+
+        let digits = BigInt::from_str(&format!("{}{}", integer, fraction))
+            .map_err(|_| ParseRationalError)?;
+        let exponent = exponent - fraction.len() as i32;
+        if exponent.unsigned_abs() > MAX_EXPONENT {
+            return Err(ParseRationalError);
+        }
+        let power = BigInt::from(10).pow(exponent.unsigned_abs());
+        Ok(if exponent >= 0 {
+            Self::new(sign * digits * power, 1)
+        } else {
+            Self::new(sign * digits, power)
+        })
+    }
+}
+
+impl fmt::Display for ParseRationalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid number")
+    }
+}
+
+impl std::error::Error for ParseRationalError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(s: &str) -> Result<Rational, ParseRationalError> {
+        s.parse()
+    }
+
+    #[test]
+    fn parses_fractions_decimals_and_exponents() {
+        assert_eq!(parse("7/3"), Ok(Rational::new(7, 3)));
+        assert_eq!(parse("-2.5"), Ok(Rational::new(-5, 2)));
+        assert_eq!(parse("1e3"), Ok(Rational::from(1000)));
+        assert_eq!(parse("2.5e-1"), Ok(Rational::new(1, 4)));
+        assert_eq!(parse(" 3 "), Ok(Rational::from(3)));
+        assert_eq!(parse("1.5/0.5"), Ok(Rational::from(3)));
+    }
+
+    #[test]
+    fn rejects_malformed_numbers() {
+        for s in ["1/0", "", ".", "-", "1.2.3", "abc", "1e", "2/x"] {
+            assert_eq!(parse(s), Err(ParseRationalError), "{:?}", s);
+        }
+    }
+
+    #[test]
+    fn displays_in_lowest_terms() {
+        assert_eq!(Rational::new(14, 6).to_string(), "7/3");
+        assert_eq!(Rational::new(-5, 2).to_string(), "-5/2");
+        assert_eq!(Rational::new(4, -2).to_string(), "-2");
+        assert_eq!(Rational::from(0).to_string(), "0");
+    }
+
+    #[test]
+    fn display_and_parse_round_trip() {
+        for value in [
+            Rational::new(7, 3),
+            Rational::new(-1, 8),
+            Rational::from(42),
+        ] {
+            assert_eq!(parse(&value.to_string()), Ok(value));
+        }
+    }
+}
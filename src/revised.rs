@@ -0,0 +1,769 @@
+use std::{cell::OnceCell, cmp::Ordering, collections::HashSet};
+
+use crate::{
+    log::log,
+    pricing::{Edges, Pricer},
+    scalar::Scalar,
+    simplex::{AntiCycling, PivotOutcome, Pivoting},
+    sparse::CscMatrix,
+    trace::{Tracer, primal_ratios},
+};
+
+/// Eta updates applied on top of the LU factors before the basis is
+/// factorized again from scratch.
+const REFACTORIZATION_INTERVAL: usize = 50;
+
+/// Entries below this fraction of the largest candidate are not chosen as
+/// pivots, which keeps the factors stable while leaving room to pick sparse
+/// rows.
+const PIVOT_THRESHOLD: f64 = 0.1;
+
+/// Step of a row that has not been pivoted yet.
+const UNPIVOTED: usize = usize::MAX;
+
+/// Sparse LU factorization `BQ = LU` of a basis matrix.
+///
+/// Columns are factorized left to right, sparsest first, each one by a
+/// sparse triangular solve against the columns of `L` found so far. The
+/// pivot is the entry of the shortest row of `B` among those within
+/// [`PIVOT_THRESHOLD`] of the largest one, which limits fill-in. Every solve
+/// takes and returns sparse vectors and only visits the entries of the
+/// factors its right-hand side reaches.
+pub(crate) struct Lu<T> {
+    /// Row of `B` pivoted at each step, and the step each row was pivoted at.
+    pivot_rows: Vec<usize>,
+    row_steps: Vec<usize>,
+    /// Column of `B` factorized at each step, and the step of each column.
+    order: Vec<usize>,
+    column_steps: Vec<usize>,
+    /// Below-diagonal entries of each column of `L`, by step, with its unit
+    /// diagonal left implicit, and the same entries by row.
+    lower: Vec<Vec<(usize, T)>>,
+    lower_rows: Vec<Vec<(usize, T)>>,
+    /// Above-diagonal entries of each column of `U`, by step, and the same
+    /// entries by row.
+    upper: Vec<Vec<(usize, T)>>,
+    upper_rows: Vec<Vec<(usize, T)>>,
+    diagonal: Vec<T>,
+}
+
+impl<T: Scalar> Lu<T> {
+    /// Factorizes the square matrix with the given sparse columns, each as
+    /// `(row, value)`.
+    ///
+    /// Panics if the matrix is singular, which the ratio test never lets a
+    /// basis become.
+    pub(crate) fn new(columns: &[Vec<(usize, T)>]) -> Self {
+        let m = columns.len();
+        let mut row_counts = vec![0; m];
+        for &(i, _) in columns.iter().flatten() {
+            row_counts[i] += 1;
+        }
+        let mut order: Vec<usize> = (0..m).collect();
+        order.sort_by_key(|&j| columns[j].len());
+
+        let threshold = T::from_f64(PIVOT_THRESHOLD);
+        let mut pivot_rows = Vec::with_capacity(m);
+        let mut row_steps = vec![UNPIVOTED; m];
+        // Columns of `L` by row of `B` until every row has a step.
+        let mut lower: Vec<Vec<(usize, T)>> = Vec::with_capacity(m);
+        let mut upper: Vec<Vec<(usize, T)>> = Vec::with_capacity(m);
+        let mut diagonal = Vec::with_capacity(m);
+        let mut x = vec![T::zero(); m];
+
+        for &j in order.iter() {
+            for (i, a) in columns[j].iter() {
+                x[*i] = a.clone();
+            }
+            let empty = vec![];
+            let pattern = reach(m, columns[j].iter().map(|(i, _)| *i), |i| {
+                match row_steps[i] {
+                    UNPIVOTED => &empty,
+                    step => &lower[step],
+                }
+            });
+            for &i in pattern.iter() {
+                let step = row_steps[i];
+                if step == UNPIVOTED || x[i] == T::zero() {
+                    continue;
+                }
+                for (r, l) in lower[step].iter() {
+                    x[*r] = x[*r].clone() - l.clone() * x[i].clone();
+                }
+            }
+
+            let mut column = vec![];
+            let mut candidates = vec![];
+            for &i in pattern.iter() {
+                let value = std::mem::replace(&mut x[i], T::zero());
+                if value == T::zero() {
+                    continue;
+                }
+                match row_steps[i] {
+                    UNPIVOTED => candidates.push((i, value)),
+                    step => column.push((step, value)),
+                }
+            }
+            let largest = candidates
+                .iter()
+                .map(|(_, v)| v.abs())
+                .fold(T::zero(), |a, b| if b > a { b } else { a });
+            assert!(!largest.is_zero(), "singular basis");
+            let bar = threshold.clone() * largest;
+            let (k, _) = candidates
+                .iter()
+                .enumerate()
+                .filter(|(_, (_, v))| v.abs() >= bar)
+                .min_by(|(_, (a, u)), (_, (b, v))| {
+                    row_counts[*a]
+                        .cmp(&row_counts[*b])
+                        .then(v.abs().partial_cmp(&u.abs()).unwrap_or(Ordering::Equal))
+                })
+                .unwrap();
+            let (pivot_row, pivot) = candidates.swap_remove(k);
+
+            row_steps[pivot_row] = pivot_rows.len();
+            pivot_rows.push(pivot_row);
+            lower.push(
+                candidates
+                    .into_iter()
+                    .map(|(i, v)| (i, v / pivot.clone()))
+                    .collect(),
+            );
+            upper.push(column);
+            diagonal.push(pivot);
+        }
+
+        // Now that every row has a step, `L` can be indexed by steps too.
+        for column in lower.iter_mut() {
+            for (i, _) in column.iter_mut() {
+                *i = row_steps[*i];
+            }
+        }
+        let mut column_steps = vec![0; m];
+        for (step, &j) in order.iter().enumerate() {
+            column_steps[j] = step;
+        }
+        Self {
+            pivot_rows,
+            row_steps,
+            column_steps,
+            order,
+            lower_rows: transpose(&lower),
+            lower,
+            upper_rows: transpose(&upper),
+            upper,
+            diagonal,
+        }
+    }
+
+    /// Solves `Bx = a` for a sparse `a` given by row, returning the non-zero
+    /// entries of `x` by column of `B`.
+    pub(crate) fn solve(&self, a: impl IntoIterator<Item = (usize, T)>) -> Vec<(usize, T)> {
+        let w = substitute(
+            &self.lower,
+            None,
+            a.into_iter().map(|(i, v)| (self.row_steps[i], v)),
+        );
+        substitute(&self.upper, Some(&self.diagonal), w)
+            .into_iter()
+            .map(|(step, v)| (self.order[step], v))
+            .collect()
+    }
+
+    /// Solves `Bᵀy = c` for a sparse `c` given by column of `B`, returning
+    /// the non-zero entries of `y` by row.
+    pub(crate) fn solve_transposed(
+        &self,
+        c: impl IntoIterator<Item = (usize, T)>,
+    ) -> Vec<(usize, T)> {
+        let v = substitute(
+            &self.upper_rows,
+            Some(&self.diagonal),
+            c.into_iter().map(|(j, v)| (self.column_steps[j], v)),
+        );
+        substitute(&self.lower_rows, None, v)
+            .into_iter()
+            .map(|(step, v)| (self.pivot_rows[step], v))
+            .collect()
+    }
+}
+
+/// Solves a triangular system by substitution, where solving for unknown
+/// `k` subtracts `a·x_k` from unknown `l` for every `(l, a)` in
+/// `adjacent[k]`, after dividing by `diagonal[k]` if given. Only the
+/// unknowns `b` reaches are visited.
+fn substitute<T: Scalar>(
+    adjacent: &[Vec<(usize, T)>],
+    diagonal: Option<&[T]>,
+    b: impl IntoIterator<Item = (usize, T)>,
+) -> Vec<(usize, T)> {
+    let mut x = vec![T::zero(); adjacent.len()];
+    let mut starts = vec![];
+    for (k, v) in b {
+        x[k] = x[k].clone() + v;
+        starts.push(k);
+    }
+    let order = reach(adjacent.len(), starts, |k| &adjacent[k]);
+    for &k in order.iter() {
+        if x[k] == T::zero() {
+            continue;
+        }
+        if let Some(diagonal) = diagonal {
+            x[k] = x[k].clone() / diagonal[k].clone();
+        }
+        for (l, a) in adjacent[k].iter() {
+            x[*l] = x[*l].clone() - a.clone() * x[k].clone();
+        }
+    }
+    order
+        .into_iter()
+        .map(|k| (k, std::mem::replace(&mut x[k], T::zero())))
+        .filter(|(_, v)| *v != T::zero())
+        .collect()
+}
+
+/// Nodes reachable from `starts` along the edges `next` gives out of each of
+/// the `n` nodes, ordered so every node comes before the ones it leads to.
+fn reach<'a, T: 'a>(
+    n: usize,
+    starts: impl IntoIterator<Item = usize>,
+    next: impl Fn(usize) -> &'a [(usize, T)],
+) -> Vec<usize> {
+    let mut visited = vec![false; n];
+    let mut finished = vec![];
+    let mut stack: Vec<(usize, usize)> = vec![];
+    for start in starts {
+        if visited[start] {
+            continue;
+        }
+        visited[start] = true;
+        stack.push((start, 0));
+        while let Some((node, edge)) = stack.last_mut() {
+            match next(*node).get(*edge) {
+                Some(&(to, _)) => {
+                    *edge += 1;
+                    if !visited[to] {
+                        visited[to] = true;
+                        stack.push((to, 0));
+                    }
+                }
+                None => {
+                    finished.push(*node);
+                    stack.pop();
+                }
+            }
+        }
+    }
+    finished.reverse();
+    finished
+}
+
+/// The entries of `columns` grouped by row instead, each as `(column, value)`.
+fn transpose<T: Clone>(columns: &[Vec<(usize, T)>]) -> Vec<Vec<(usize, T)>> {
+    let mut rows = vec![vec![]; columns.len()];
+    for (j, column) in columns.iter().enumerate() {
+        for (i, v) in column.iter() {
+            rows[*i].push((j, v.clone()));
+        }
+    }
+    rows
+}
+
+/// One basis change in product form: the column `B⁻¹a` of the entering
+/// variable, stored sparsely, which replaced the basic variable of `row`.
+struct Eta<T> {
+    row: usize,
+    pivot: T,
+    column: Vec<(usize, T)>,
+}
+
+/// Inverse of the basis as the LU factors of an earlier basis followed by the
+/// eta file of every pivot since.
+struct Factorization<T> {
+    lu: Lu<T>,
+    etas: Vec<Eta<T>>,
+}
+
+impl<T: Scalar> Factorization<T> {
+    fn new(columns: &CscMatrix<T>, basis: &[usize]) -> Self {
+        let basic: Vec<Vec<(usize, T)>> = basis
+            .iter()
+            .map(|&j| columns.column(j).map(|(i, a)| (i, a.clone())).collect())
+            .collect();
+        Self {
+            lu: Lu::new(&basic),
+            etas: vec![],
+        }
+    }
+
+    /// `B⁻¹a`, for a sparse `a`.
+    fn ftran(&self, a: impl IntoIterator<Item = (usize, T)>) -> Vec<T> {
+        let mut x = dense(self.lu.solve(a), self.lu.diagonal.len());
+        for Eta { row, pivot, column } in self.etas.iter() {
+            if x[*row] == T::zero() {
+                continue;
+            }
+            let step = x[*row].clone() / pivot.clone();
+            for (i, e) in column.iter().filter(|(i, _)| i != row) {
+                x[*i] = x[*i].clone() - e.clone() * step.clone();
+            }
+            x[*row] = step;
+        }
+        x
+    }
+
+    /// `cᵀB⁻¹`, as a column.
+    fn btran(&self, c: &[T]) -> Vec<T> {
+        let mut c = c.to_vec();
+        for Eta { row, pivot, column } in self.etas.iter().rev() {
+            let sum = column
+                .iter()
+                .filter(|(i, _)| i != row)
+                .fold(c[*row].clone(), |sum, (i, e)| {
+                    sum - c[*i].clone() * e.clone()
+                });
+            c[*row] = sum / pivot.clone();
+        }
+        dense(self.lu.solve_transposed(sparse(&c)), c.len())
+    }
+}
+
+/// The entries of `x` that are not zero, as `(index, value)`.
+pub(crate) fn sparse<T: Scalar>(x: &[T]) -> Vec<(usize, T)> {
+    x.iter()
+        .enumerate()
+        .filter(|(_, v)| **v != T::zero())
+        .map(|(i, v)| (i, v.clone()))
+        .collect()
+}
+
+/// The vector of length `n` with the given entries and zeros elsewhere.
+pub(crate) fn dense<T: Scalar>(entries: Vec<(usize, T)>, n: usize) -> Vec<T> {
+    let mut x = vec![T::zero(); n];
+    for (i, v) in entries {
+        x[i] = v;
+    }
+    x
+}
+/// What limits the step of the entering variable.
+enum Step {
+    /// The entering variable reaches its other bound and no basis change is
+    /// needed.
+    Flip,
+    /// The basic variable of the row reaches its upper bound if the flag is
+    /// set and zero otherwise.
+    Leave(usize, bool),
+}
+
+/// Revised simplex method over `Ax = b, 0 ≤ x ≤ u`.
+///
+/// Instead of rewriting a whole tableau on every pivot it keeps `A` as given
+/// and works with a factorization of the basis matrix `B`: prices come from
+/// `cᵀB⁻¹` and the entering column from `B⁻¹a`. Each pivot appends an eta
+/// update to the factorization, which is rebuilt from scratch every
+/// [`REFACTORIZATION_INTERVAL`] pivots to keep it short and accurate.
+///
+/// Upper bounds are handled as in the bounded-variable simplex, with every
+/// non-basic variable at either of its bounds.
+///
+/// `A` is stored by sparse columns, so pricing only touches its non-zero
+/// entries and only the basis is ever held densely.
+pub(crate) struct RevisedSimplex<T> {
+    columns: CscMatrix<T>,
+    rhs: Vec<T>,
+    upper: Vec<Option<T>>,
+    /// Basic column of each row.
+    basis: Vec<usize>,
+    /// Whether each non-basic column sits at its upper bound.
+    at_upper: Vec<bool>,
+    factorization: Factorization<T>,
+    /// Value of the basic variable of each row.
+    basic_values: Vec<T>,
+}
+
+impl<T: Scalar> RevisedSimplex<T> {
+    /// Starts from `basis`, which must be a non-singular choice of columns
+    /// with every basic variable within its bounds.
+    pub(crate) fn new(
+        columns: CscMatrix<T>,
+        rhs: Vec<T>,
+        upper: Vec<Option<T>>,
+        basis: Vec<usize>,
+    ) -> Self {
+        let factorization = Factorization::new(&columns, &basis);
+        let at_upper = vec![false; columns.columns()];
+        let mut revised = Self {
+            columns,
+            rhs,
+            upper,
+            basis,
+            at_upper,
+            factorization,
+            basic_values: vec![],
+        };
+        revised.update_basic_values();
+        revised
+    }
+
+    /// Recomputes the basic variables from scratch as `B⁻¹(b - Σ a_j u_j)`
+    /// over the columns at their upper bound.
+    fn update_basic_values(&mut self) {
+        let mut rhs = self.rhs.clone();
+        for j in 0..self.columns.columns() {
+            if let (true, Some(u)) = (self.at_upper[j], &self.upper[j]) {
+                for (i, a) in self.columns.column(j) {
+                    rhs[i] = rhs[i].clone() - a.clone() * u.clone();
+                }
+            }
+        }
+        self.basic_values = self.factorization.ftran(sparse(&rhs));
+    }
+
+    /// Value of every column.
+    pub(crate) fn values(&self) -> Vec<T> {
+        let mut values: Vec<T> = (0..self.columns.columns())
+            .map(|j| match (&self.upper[j], self.at_upper[j]) {
+                (Some(u), true) => u.clone(),
+                _ => T::zero(),
+            })
+            .collect();
+        for (&b, v) in self.basis.iter().zip(self.basic_values.iter()) {
+            values[b] = v.clone();
+        }
+        values
+    }
+
+    /// Basic column of each row.
+    pub(crate) fn basis(&self) -> &[usize] {
+        &self.basis
+    }
+
+    pub(crate) fn upper(&self) -> &[Option<T>] {
+        &self.upper
+    }
+
+    /// Whether each column sits at its upper bound, when non-basic.
+    pub(crate) fn at_upper(&self) -> &[bool] {
+        &self.at_upper
+    }
+
+    /// Dual value of each row, `cᵀB⁻¹`.
+    pub(crate) fn prices(&self, costs: &[T]) -> Vec<T> {
+        let basic_costs: Vec<T> = self.basis.iter().map(|&b| costs[b].clone()).collect();
+        self.factorization.btran(&basic_costs)
+    }
+
+    /// `B⁻¹a_j`, column `j` in terms of the current basis.
+    fn ftran_column(&self, j: usize) -> Vec<T> {
+        self.factorization
+            .ftran(self.columns.column(j).map(|(i, a)| (i, a.clone())))
+    }
+
+    /// `cᵀx` at the current point.
+    pub(crate) fn objective(&self, costs: &[T]) -> T {
+        costs
+            .iter()
+            .zip(self.values())
+            .fold(T::zero(), |sum, (c, x)| sum + c.clone() * x)
+    }
+
+    /// Fixes every column from `first` on at zero, as phase 2 does with the
+    /// artificial variables. Those still basic stay there at zero.
+    pub(crate) fn fix_from(&mut self, first: usize) {
+        for j in first..self.columns.columns() {
+            self.upper[j] = Some(T::zero());
+            self.at_upper[j] = false;
+        }
+    }
+
+    /// Maximizes `cᵀx`, letting only the columns before `eligible` enter the
+    /// basis.
+    ///
+    /// Prices with the [`PricingRule`] of `pivoting`, or Bland's rule if its
+    /// [`AntiCycling`] asks for it or once a basis repeats. With [`AntiCycling::Lexicographic`], ratio
+    /// ties go to the lexicographically smallest row of `B⁻¹[b | B₀]`, where
+    /// `B₀` is the basis this call started from.
+    pub(crate) fn optimize(
+        &mut self,
+        costs: &[T],
+        eligible: usize,
+        pivoting: Pivoting,
+        iterations: &mut usize,
+        tracer: &mut Tracer<T>,
+    ) -> PivotOutcome {
+        let Pivoting {
+            pricing_rule,
+            anti_cycling,
+            max_iterations,
+        } = pivoting;
+        let mut bland = anti_cycling == AntiCycling::Bland;
+        let mut seen: HashSet<(Vec<usize>, Vec<bool>)> = HashSet::new();
+        let reference = self.basis.clone();
+        let mut pricer = pricing_rule.pricer();
+
+        loop {
+            if !bland {
+                let mut key = self.basis.clone();
+                key.sort_unstable();
+                if !seen.insert((key, self.at_upper.clone())) {
+                    log("Basis repeated, switching to Bland's rule");
+                    bland = true;
+                }
+            }
+
+            let entering = self.price(costs, eligible, bland, pricer.as_mut());
+            if entering.is_none() || *iterations >= max_iterations {
+                self.trace(tracer, costs, None, None);
+            }
+            let Some(entering) = entering else {
+                return PivotOutcome::Optimal;
+            };
+            if *iterations >= max_iterations {
+                return PivotOutcome::IterationLimit;
+            }
+
+            let column = self.ftran_column(entering);
+            let increasing = !self.at_upper[entering];
+            let lexicographic = (anti_cycling == AntiCycling::Lexicographic && !bland)
+                .then_some(reference.as_slice());
+            let limit = self.ratio_test(entering, &column, increasing, bland, lexicographic);
+            let leaving = match &limit {
+                Some((_, Step::Leave(row, _))) => Some(*row),
+                _ => None,
+            };
+            self.trace(tracer, costs, Some(entering), leaving);
+            let Some((step, limit)) = limit else {
+                return PivotOutcome::Unbounded(entering);
+            };
+
+            for (x, w) in self.basic_values.iter_mut().zip(column.iter()) {
+                let change = step.clone() * w.clone();
+                *x = if increasing {
+                    x.clone() - change
+                } else {
+                    x.clone() + change
+                };
+            }
+            match limit {
+                Step::Flip => self.at_upper[entering] = !self.at_upper[entering],
+                Step::Leave(row, to_upper) => {
+                    // Row `row` of `B⁻¹A`, worked out only if the pricer
+                    // reads it.
+                    let rho = OnceCell::new();
+                    let pivot_row = |j: usize| {
+                        let rho = rho.get_or_init(|| {
+                            let mut unit = vec![T::zero(); self.rhs.len()];
+                            unit[row] = T::one();
+                            self.factorization.btran(&unit)
+                        });
+                        self.columns.dot_column(j, rho)
+                    };
+                    pricer.pivoted(&pivot_row, entering, self.basis[row]);
+                    let value = match (&self.upper[entering], increasing) {
+                        (Some(u), false) => u.clone() - step,
+                        _ => step,
+                    };
+                    self.at_upper[self.basis[row]] = to_upper;
+                    self.basis[row] = entering;
+                    self.at_upper[entering] = false;
+                    self.basic_values[row] = value;
+                    self.pivoted(row, column);
+                }
+            }
+            *iterations += 1;
+        }
+    }
+
+    /// Records the tableau of the current basis in `tracer`, with every
+    /// column at its upper bound complemented as the tableau method does.
+    fn trace(
+        &self,
+        tracer: &mut Tracer<T>,
+        costs: &[T],
+        entering: Option<usize>,
+        leaving: Option<usize>,
+    ) {
+        if !tracer.is_on() {
+            return;
+        }
+        let m = self.rhs.len();
+        let prices = self.prices(costs);
+        let mut matrix = vec![vec![]; m + 1];
+        for (j, cost) in costs.iter().enumerate() {
+            let complemented = self.at_upper[j] && !self.basis.contains(&j);
+            let sign = |v: T| if complemented { -v } else { v };
+            for (row, w) in matrix.iter_mut().zip(self.ftran_column(j)) {
+                row.push(sign(w));
+            }
+            let reduced = cost.clone() - self.columns.dot_column(j, &prices);
+            matrix[m].push(sign(-reduced));
+        }
+        for (row, x) in matrix.iter_mut().zip(self.basic_values.iter()) {
+            row.push(x.clone());
+        }
+        matrix[m].push(self.objective(costs));
+
+        let complemented: Vec<bool> = (0..self.columns.columns())
+            .map(|j| self.at_upper[j] && !self.basis.contains(&j))
+            .collect();
+        let upper = &self.upper;
+        tracer.record(
+            &matrix,
+            &self.basis,
+            &complemented,
+            entering,
+            leaving,
+            || entering.map_or_else(Vec::new, |j| primal_ratios(&matrix, j, &self.basis, upper)),
+        );
+    }
+
+    /// Column to enter the basis: one at its lower bound with a positive
+    /// reduced cost or at its upper bound with a negative one, chosen by
+    /// `pricer` or by Bland's rule.
+    fn price(
+        &self,
+        costs: &[T],
+        eligible: usize,
+        bland: bool,
+        pricer: &mut dyn Pricer<T>,
+    ) -> Option<usize> {
+        let prices = self.prices(costs);
+        let mut basic = vec![false; costs.len()];
+        for &b in self.basis.iter() {
+            basic[b] = true;
+        }
+
+        // Reduced costs as the tableau shows them, negative where entering
+        // improves the objective, with every column that cannot enter at zero.
+        let mut reduced = vec![T::zero(); costs.len()];
+        for (j, cost) in costs.iter().enumerate().take(eligible) {
+            if !basic[j] {
+                let d = self.columns.dot_column(j, &prices) - cost.clone();
+                reduced[j] = if self.at_upper[j] { -d } else { d };
+            }
+        }
+        if bland {
+            return reduced.iter().position(|d| d.is_negative());
+        }
+        pricer.select(&reduced, self)
+    }
+
+    /// Longest step the entering column can take, given `column = B⁻¹a`, and
+    /// what stops it. Ties go to a row leaving at zero, then to the lowest
+    /// row, the lowest basic variable under Bland's rule, or the
+    /// lexicographically smallest row over the `lexicographic` reference
+    /// columns if given. `None` if nothing does.
+    fn ratio_test(
+        &self,
+        entering: usize,
+        column: &[T],
+        increasing: bool,
+        bland: bool,
+        lexicographic: Option<&[usize]>,
+    ) -> Option<(T, Step)> {
+        let signed = |w: &T| if increasing { w.clone() } else { -w.clone() };
+        let mut best: Option<(T, Step)> = None;
+        for (i, w) in column.iter().enumerate() {
+            let w = signed(w);
+            let x = &self.basic_values[i];
+            let (ratio, to_upper) = if w.is_positive() {
+                (x.clone() / w, false)
+            } else if let (true, Some(u)) = (w.is_negative(), &self.upper[self.basis[i]]) {
+                ((u.clone() - x.clone()) / -w, true)
+            } else {
+                continue;
+            };
+
+            let better = match &best {
+                None => true,
+                Some((step, Step::Leave(r, r_to_upper))) => {
+                    let difference = ratio.clone() - step.clone();
+                    let tied_at_zero = !to_upper && !*r_to_upper;
+                    difference.is_negative()
+                        || !difference.is_positive()
+                            && ((*r_to_upper && !to_upper)
+                                || (bland
+                                    && *r_to_upper == to_upper
+                                    && self.basis[i] < self.basis[*r])
+                                || lexicographic.is_some_and(|reference| {
+                                    tied_at_zero
+                                        && self.lexicographically_smaller(
+                                            (i, signed(&column[i])),
+                                            (*r, signed(&column[*r])),
+                                            reference,
+                                        )
+                                }))
+                }
+                Some((_, Step::Flip)) => unreachable!(),
+            };
+            if better {
+                best = Some((ratio, Step::Leave(i, to_upper)));
+            }
+        }
+
+        if let Some(u) = &self.upper[entering]
+            && best
+                .as_ref()
+                .is_none_or(|(step, _)| (u.clone() - step.clone()).is_negative())
+        {
+            best = Some((u.clone(), Step::Flip));
+        }
+        best
+    }
+
+    /// Whether row `i` of `B⁻¹B₀` divided by `w_i` is lexicographically
+    /// smaller than row `r` divided by `w_r`, where `B₀` has the `reference`
+    /// columns and each row comes with its entry `w` of the entering column.
+    fn lexicographically_smaller(
+        &self,
+        (i, w_i): (usize, T),
+        (r, w_r): (usize, T),
+        reference: &[usize],
+    ) -> bool {
+        let scaled_row = |k: usize, w: T| -> Vec<T> {
+            let mut unit = vec![T::zero(); self.rhs.len()];
+            unit[k] = T::one();
+            let row = self.factorization.btran(&unit);
+            reference
+                .iter()
+                .map(|&j| self.columns.dot_column(j, &row) / w.clone())
+                .collect()
+        };
+        scaled_row(i, w_i)
+            .into_iter()
+            .zip(scaled_row(r, w_r))
+            .map(|(a, b)| a - b)
+            .find(|difference| !difference.is_zero())
+            .is_some_and(|difference| difference.is_negative())
+    }
+
+    /// Records the basis change of `row`, whose entering column was
+    /// `column = B⁻¹a`, refactorizing once the eta file grows too long.
+    fn pivoted(&mut self, row: usize, column: Vec<T>) {
+        self.factorization.etas.push(Eta {
+            row,
+            pivot: column[row].clone(),
+            column: sparse(&column),
+        });
+        if self.factorization.etas.len() >= REFACTORIZATION_INTERVAL {
+            self.factorization = Factorization::new(&self.columns, &self.basis);
+            self.update_basic_values();
+        }
+    }
+}
+
+impl<T: Scalar> Edges<T> for RevisedSimplex<T> {
+    fn squared_length(&self, j: usize) -> f64 {
+        self.ftran_column(j)
+            .iter()
+            .map(|w| w.to_f64().powi(2))
+            .sum()
+    }
+
+    fn step(&self, j: usize) -> Option<T> {
+        let column = self.ftran_column(j);
+        self.ratio_test(j, &column, !self.at_upper[j], false, None)
+            .map(|(step, _)| step)
+    }
+}
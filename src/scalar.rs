@@ -0,0 +1,98 @@
+use std::{
+    fmt,
+    ops::{Add, Div, Mul, Neg, Sub},
+};
+
+/// Numbers a [`Simplex`](crate::simplex::Simplex) can be solved over: a field
+/// with a sign test that tolerates rounding noise.
+///
+/// Implemented for `f32` and `f64`, which compare against a small tolerance,
+/// for [`Rational`](crate::rational::Rational), which is exact, and for the
+/// [`BigM`](crate::big_m::BigM) entries of a Big-M tableau.
+pub trait Scalar:
+    Clone
+    + fmt::Debug
+    + fmt::Display
+    + PartialEq
+    + PartialOrd
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Neg<Output = Self>
+{
+    fn zero() -> Self;
+
+    fn one() -> Self;
+
+    /// Closest value to `value`, used for the weights of pricing rules.
+    fn from_f64(value: f64) -> Self;
+
+    /// Approximate value, used for the weights of pricing rules.
+    fn to_f64(&self) -> f64;
+
+    /// Whether the value is below zero, beyond rounding noise.
+    fn is_negative(&self) -> bool;
+
+    /// Whether the value is above zero, beyond rounding noise.
+    fn is_positive(&self) -> bool;
+
+    /// Largest integer not above the value.
+    fn floor(&self) -> Self;
+
+    /// Whether the value is an integer, up to rounding noise.
+    fn is_integer(&self) -> bool {
+        let fraction = self.clone() - self.floor();
+        fraction.is_zero() || (Self::one() - fraction).is_zero()
+    }
+
+    fn abs(&self) -> Self {
+        if self.is_negative() {
+            -self.clone()
+        } else {
+            self.clone()
+        }
+    }
+
+    /// Whether the value is zero, up to rounding noise.
+    fn is_zero(&self) -> bool {
+        !self.is_negative() && !self.is_positive()
+    }
+}
+
+macro_rules! float_scalar {
+    ($float:ty, $epsilon:expr) => {
+        impl Scalar for $float {
+            fn zero() -> Self {
+                0.0
+            }
+
+            fn one() -> Self {
+                1.0
+            }
+
+            fn from_f64(value: f64) -> Self {
+                value as $float
+            }
+
+            fn to_f64(&self) -> f64 {
+                *self as f64
+            }
+
+            fn is_negative(&self) -> bool {
+                *self < -$epsilon
+            }
+
+            fn is_positive(&self) -> bool {
+                *self > $epsilon
+            }
+
+            fn floor(&self) -> Self {
+                <$float>::floor(*self)
+            }
+        }
+    };
+}
+
+float_scalar!(f32, 1e-5);
+float_scalar!(f64, 1e-9);
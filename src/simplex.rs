@@ -1,193 +1,2520 @@
-use std::collections::HashMap;
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+};
 
-#[derive(Debug, Default)]
-pub struct Simplex {
+use crate::{
+    big_m::BigM,
+    branch_and_bound::{BranchingRule, DEFAULT_MAX_NODES, NodeSelection},
+    log::log,
+    pricing::{Edges, PricingRule},
+    ranging::{Canonical, tighten},
+    revised::RevisedSimplex,
+    scalar::Scalar,
+    sparse::{CscMatrix, CsrMatrix},
+    trace::{Iteration, Stage, Tracer, primal_ratios},
+};
+
+#[derive(Debug, Clone)]
+pub struct Simplex<T> {
     variables: Vec<String>,
-    constraints: Vec<Vec<f32>>,
-    target: Vec<f32>,
+    /// Bounds of each variable, in the order of `variables`.
+    bounds: Vec<Bounds<T>>,
+    /// Values each variable may take, in the order of `variables`.
+    kinds: Vec<VariableKind>,
+    /// Coefficient of each variable in every constraint, stored sparsely.
+    constraints: CsrMatrix<T>,
+    /// Right-hand side of every constraint.
+    rhs: Vec<T>,
+    relations: Vec<Relation>,
+    target: Vec<T>,
+    objective_sense: ObjectiveSense,
+    algorithm: Algorithm,
+    start_method: StartMethod,
+    pricing_rule: PricingRule,
+    anti_cycling: AntiCycling,
+    max_iterations: usize,
+    node_selection: NodeSelection,
+    branching_rule: BranchingRule,
+    max_nodes: usize,
+}
+
+impl<T> Default for Simplex<T> {
+    fn default() -> Self {
+        Self {
+            variables: Default::default(),
+            bounds: Default::default(),
+            kinds: Default::default(),
+            constraints: Default::default(),
+            rhs: Default::default(),
+            relations: Default::default(),
+            target: Default::default(),
+            objective_sense: Default::default(),
+            algorithm: Default::default(),
+            start_method: Default::default(),
+            pricing_rule: Default::default(),
+            anti_cycling: Default::default(),
+            max_iterations: DEFAULT_MAX_ITERATIONS,
+            node_selection: Default::default(),
+            branching_rule: Default::default(),
+            max_nodes: DEFAULT_MAX_NODES,
+        }
+    }
+}
+
+/// Outcome of [`Simplex::run_simplex`]. Every variant records the number of
+/// pivots performed.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SolveResult<T> {
+    /// An optimal solution was found.
+    Optimal {
+        iterations: usize,
+        objective: T,
+        values: Vec<(String, T)>,
+        sensitivity: Sensitivity<T>,
+    },
+    /// No point satisfies every constraint. `infeasibility` is the smallest
+    /// sum of artificial variables the start method could reach, or how far
+    /// a variable's lower bound lies above its upper bound.
+    Infeasible { iterations: usize, infeasibility: T },
+    /// The objective improves without limit as `variable` increases.
+    Unbounded { iterations: usize, variable: String },
+    /// The solver gave up after `iterations` pivots. Carries the last basic
+    /// solution it visited, which may not be feasible if it stopped in
+    /// phase 1.
+    IterationLimit {
+        iterations: usize,
+        objective: T,
+        values: Vec<(String, T)>,
+    },
+}
+
+/// Sign restriction on a decision variable, a shorthand for the most common
+/// [`Bounds`].
+#[derive(Debug, Default, Clone, PartialEq)]
+pub enum VariableSign<T> {
+    #[default]
+    NonNegative,
+    NonPositive,
+    Free,
+    /// `x ≥ bound` for any bound.
+    AtLeast(T),
+}
+
+impl<T: fmt::Display> fmt::Display for VariableSign<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VariableSign::NonNegative => write!(f, "≥ 0"),
+            VariableSign::NonPositive => write!(f, "≤ 0"),
+            VariableSign::Free => write!(f, "free"),
+            VariableSign::AtLeast(bound) => write!(f, "≥ {}", bound),
+        }
+    }
+}
+
+/// Lower and upper bound of a decision variable, `None` standing for an
+/// infinite one. Variables start out as `0 ≤ x`.
+///
+/// Finite upper bounds are handled by the bounded-variable simplex without
+/// adding constraint rows. Any other lower bound is substituted away before
+/// solving: `x = l + x'` for a finite one, `x = u - x'` if only the upper
+/// bound is finite and `x = x' - x''` for a free variable.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bounds<T> {
+    pub lower: Option<T>,
+    pub upper: Option<T>,
+}
+
+impl<T: Scalar> Bounds<T> {
+    /// Whether the bounds are `0 ≤ x ≤ u` for some `u ≥ 0`, the form the
+    /// tableau works with directly.
+    fn is_standard(&self) -> bool {
+        self.lower.as_ref().is_some_and(|l| l.is_zero())
+            && !self.upper.as_ref().is_some_and(|u| u.is_negative())
+    }
+}
+
+impl<T: Scalar> Default for Bounds<T> {
+    fn default() -> Self {
+        Self {
+            lower: Some(T::zero()),
+            upper: None,
+        }
+    }
+}
+
+impl<T: Scalar> From<VariableSign<T>> for Bounds<T> {
+    fn from(sign: VariableSign<T>) -> Self {
+        match sign {
+            VariableSign::NonNegative => Self::default(),
+            VariableSign::NonPositive => Self {
+                lower: None,
+                upper: Some(T::zero()),
+            },
+            VariableSign::Free => Self {
+                lower: None,
+                upper: None,
+            },
+            VariableSign::AtLeast(bound) => Self {
+                lower: Some(bound),
+                upper: None,
+            },
+        }
+    }
+}
+
+/// Values a decision variable may take.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum VariableKind {
+    #[default]
+    Continuous,
+    /// Integer values, which only [`Simplex::run_branch_and_bound`] and
+    /// [`Simplex::run_gomory`] enforce.
+    Integer,
+    /// `0` or `1`: an integer variable with the implicit bounds `0 ≤ x ≤ 1`
+    /// on top of any it is given.
+    Binary,
+}
+
+impl VariableKind {
+    pub fn is_integer(self) -> bool {
+        self != VariableKind::Continuous
+    }
+
+    /// Next kind in the `Continuous → Integer → Binary` cycle, used by the UI
+    /// toggle.
+    pub fn next(self) -> Self {
+        match self {
+            VariableKind::Continuous => VariableKind::Integer,
+            VariableKind::Integer => VariableKind::Binary,
+            VariableKind::Binary => VariableKind::Continuous,
+        }
+    }
+}
+
+impl fmt::Display for VariableKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VariableKind::Continuous => write!(f, "Continuous"),
+            VariableKind::Integer => write!(f, "Integer"),
+            VariableKind::Binary => write!(f, "Binary"),
+        }
+    }
 }
 
+/// Whether the target function is maximized or minimized.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ObjectiveSense {
+    #[default]
+    Maximize,
+    Minimize,
+}
+
+impl fmt::Display for ObjectiveSense {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ObjectiveSense::Maximize => write!(f, "Max"),
+            ObjectiveSense::Minimize => write!(f, "Min"),
+        }
+    }
+}
+
+/// Implementation of the simplex method used to solve the problem.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Algorithm {
+    /// Pivots on a dense tableau, honoring every setting.
+    #[default]
+    Tableau,
+    /// Revised simplex over an LU-factorized basis, see [`RevisedSimplex`].
+    /// Always starts with the two-phase method, breaking ratio ties and
+    /// switching to Bland's rule as [`AntiCycling`] says.
+    Revised,
+    /// Dual simplex from the all-slack basis, see [`Simplex::run_dual`].
+    Dual,
+}
+
+impl Algorithm {
+    /// Next algorithm in the `Tableau → Revised → Dual` cycle, used by the UI
+    /// toggle.
+    pub fn next(self) -> Self {
+        match self {
+            Algorithm::Tableau => Algorithm::Revised,
+            Algorithm::Revised => Algorithm::Dual,
+            Algorithm::Dual => Algorithm::Tableau,
+        }
+    }
+}
+
+impl fmt::Display for Algorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Algorithm::Tableau => write!(f, "Tableau"),
+            Algorithm::Revised => write!(f, "Revised"),
+            Algorithm::Dual => write!(f, "Dual simplex"),
+        }
+    }
+}
+
+/// Sensitivity of an optimal solution, read from its optimal basis. Every
+/// per-constraint list follows the order the constraints were added in.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sensitivity<T> {
+    /// Change of the objective per unit increase of each constraint's
+    /// right-hand side, the dual value of the constraint.
+    pub shadow_prices: Vec<T>,
+    /// Change of the objective per unit increase of each variable, zero for
+    /// basic variables.
+    pub reduced_costs: Vec<(String, T)>,
+    /// Slack of each `≤` row or surplus of each `≥` row, zero for `=` rows.
+    pub slacks: Vec<T>,
+    /// Whether each constraint holds with equality at the optimum.
+    pub binding: Vec<bool>,
+    /// Range of each objective coefficient over which the optimal basis
+    /// stays optimal.
+    pub objective_ranges: Vec<AllowableRange<T>>,
+    /// Range of each constraint's right-hand side over which the optimal
+    /// basis stays feasible, and the shadow price valid.
+    pub rhs_ranges: Vec<AllowableRange<T>>,
+    /// Whether a non-basic column has a zero reduced cost, so pivoting it in
+    /// keeps the objective value: the optimum is usually not unique, see
+    /// [`Simplex::optimal_face`]. At a degenerate vertex such a pivot may
+    /// only change the basis.
+    pub alternative_optima: bool,
+}
+
+impl<T> Default for Sensitivity<T> {
+    fn default() -> Self {
+        Self {
+            shadow_prices: vec![],
+            reduced_costs: vec![],
+            slacks: vec![],
+            binding: vec![],
+            objective_ranges: vec![],
+            rhs_ranges: vec![],
+            alternative_optima: false,
+        }
+    }
+}
+
+/// How far a coefficient can move from its current value before the optimal
+/// basis changes, with `None` for no limit.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AllowableRange<T> {
+    pub value: T,
+    pub increase: Option<T>,
+    pub decrease: Option<T>,
+}
+
+/// How the initial feasible basis is obtained when `≥` or `=` rows need
+/// artificial variables.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum StartMethod {
+    #[default]
+    TwoPhase,
+    BigM,
+}
+
+impl fmt::Display for StartMethod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StartMethod::TwoPhase => write!(f, "Two-phase"),
+            StartMethod::BigM => write!(f, "Big-M"),
+        }
+    }
+}
+
+impl<T> SolveResult<T> {
+    pub fn iterations(&self) -> usize {
+        match self {
+            SolveResult::Optimal { iterations, .. }
+            | SolveResult::Infeasible { iterations, .. }
+            | SolveResult::Unbounded { iterations, .. }
+            | SolveResult::IterationLimit { iterations, .. } => *iterations,
+        }
+    }
+}
+
+/// Rule used to keep degenerate problems from pivoting in a cycle.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum AntiCycling {
+    /// Most negative reduced cost and lowest row on ratio ties, switching to
+    /// [`AntiCycling::Bland`] as soon as a basis repeats.
+    #[default]
+    Automatic,
+    /// Bland's rule: the lowest-index column with a negative reduced cost
+    /// enters, and ratio ties go to the lowest-index basic variable.
+    Bland,
+    /// Most negative reduced cost, with ratio ties broken by the lexicographic
+    /// ratio test.
+    Lexicographic,
+}
+
+impl AntiCycling {
+    /// Next rule in the `Automatic → Bland → Lexicographic` cycle, used by
+    /// the UI toggle.
+    pub fn next(self) -> Self {
+        match self {
+            AntiCycling::Automatic => AntiCycling::Bland,
+            AntiCycling::Bland => AntiCycling::Lexicographic,
+            AntiCycling::Lexicographic => AntiCycling::Automatic,
+        }
+    }
+}
+
+impl fmt::Display for AntiCycling {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AntiCycling::Automatic => write!(f, "Automatic"),
+            AntiCycling::Bland => write!(f, "Bland"),
+            AntiCycling::Lexicographic => write!(f, "Lexicographic"),
+        }
+    }
+}
+
+/// Relation between the left-hand side and the right-hand side of a constraint.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Relation {
+    #[default]
+    LessEqual,
+    GreaterEqual,
+    Equal,
+}
+
+impl Relation {
+    /// Next relation in the `≤ → ≥ → =` cycle, used by the UI toggle.
+    pub fn next(self) -> Self {
+        match self {
+            Relation::LessEqual => Relation::GreaterEqual,
+            Relation::GreaterEqual => Relation::Equal,
+            Relation::Equal => Relation::LessEqual,
+        }
+    }
+
+    fn flip(self) -> Self {
+        match self {
+            Relation::LessEqual => Relation::GreaterEqual,
+            Relation::GreaterEqual => Relation::LessEqual,
+            Relation::Equal => Relation::Equal,
+        }
+    }
+}
+
+impl fmt::Display for Relation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Relation::LessEqual => write!(f, "≤"),
+            Relation::GreaterEqual => write!(f, "≥"),
+            Relation::Equal => write!(f, "="),
+        }
+    }
+}
+
+const DEFAULT_MAX_ITERATIONS: usize = 1000;
+
+/// Why [`optimize`] stopped pivoting.
+pub(crate) enum PivotOutcome {
+    Optimal,
+    /// The column at this index can enter the basis but no row limits it.
+    Unbounded(usize),
+    IterationLimit,
+}
+
+/// Why [`dual_optimize`] stopped pivoting.
+enum DualOutcome {
+    Optimal,
+    /// The row at this index has a negative right-hand side but no negative
+    /// entry to pivot on, so no point satisfies it.
+    Infeasible(usize),
+    IterationLimit,
+}
+
+/// Settings [`optimize`] and [`RevisedSimplex::optimize`] pivot with.
+#[derive(Clone, Copy)]
+pub(crate) struct Pivoting {
+    pub(crate) pricing_rule: PricingRule,
+    pub(crate) anti_cycling: AntiCycling,
+    pub(crate) max_iterations: usize,
+}
+
+/// The columns of the constraint rows of [`Simplex::initial_tableau`] and its
+/// bookkeeping, without the tableau itself.
+struct InitialColumns<T> {
+    a: CscMatrix<T>,
+    rhs: Vec<T>,
+    /// Basic column of each row.
+    basis: Vec<usize>,
+    columns: Vec<String>,
+    upper: Vec<Option<T>>,
+    first_artificial: usize,
+}
+
+impl<T: Scalar> InitialColumns<T> {
+    /// The dense tableau of these columns, with a zero objective row.
+    fn tableau(self) -> Tableau<T> {
+        let width = self.columns.len();
+        let mut matrix = vec![vec![T::zero(); width + 1]; self.rhs.len() + 1];
+        for (i, j, a) in self.a.entries() {
+            matrix[i][j] = a.clone();
+        }
+        for (row, b) in matrix.iter_mut().zip(self.rhs) {
+            row[width] = b;
+        }
+        Tableau {
+            matrix,
+            basis: self.basis,
+            columns: self.columns,
+            upper: UpperBounds::new(self.upper),
+            first_artificial: self.first_artificial,
+        }
+    }
+}
+
+/// A tableau together with the bookkeeping needed to read it back.
 #[derive(Debug, Clone)]
-pub enum VariableType {
-    Basic,
-    NonBasic(String),
+pub(crate) struct Tableau<T> {
+    pub(crate) matrix: Vec<Vec<T>>,
+    /// Basic column of each constraint row.
+    pub(crate) basis: Vec<usize>,
+    /// Name of every column except the right-hand side.
+    pub(crate) columns: Vec<String>,
+    upper: UpperBounds<T>,
+    pub(crate) first_artificial: usize,
 }
 
-impl Simplex {
-    pub fn run_simplex(&self) -> (f32, Vec<(String, f32)>) {
-        print_matrix(&self.constraints);
+impl<T: Scalar> Tableau<T> {
+    pub(crate) fn has_artificials(&self) -> bool {
+        self.first_artificial < self.columns.len()
+    }
 
-        let mut matrix = vec![
-            vec![0_f32; self.variables.len() + self.constraints.len() + 1];
-            self.constraints.len() + 1
-        ];
+    /// Replaces the objective row with the phase 1 objective, maximizing
+    /// -sum(artificials), expressed in terms of the non-basic variables by
+    /// subtracting every artificial row.
+    pub(crate) fn set_phase_one_objective(&mut self) {
+        let matrix = &mut self.matrix;
+        let last = matrix.len() - 1;
+        for e in matrix[last].iter_mut() {
+            *e = T::zero();
+        }
+        for i in 0..last {
+            if self.basis[i] >= self.first_artificial {
+                for j in 0..matrix[i].len() {
+                    if j < self.first_artificial || j == matrix[i].len() - 1 {
+                        matrix[last][j] = matrix[last][j].clone() - matrix[i][j].clone();
+                    }
+                }
+            }
+        }
+    }
+
+    /// Sum of the artificial variables at the end of phase 1.
+    pub(crate) fn infeasibility(&self) -> T {
+        T::zero() - self.matrix.last().unwrap().last().unwrap().clone()
+    }
+
+    /// Drives the artificials left basic out of the basis and drops their
+    /// columns, returning those of the redundant rows removed on the way.
+    pub(crate) fn drop_artificials(&mut self) -> Vec<usize> {
+        let redundant =
+            drive_out_artificials(&mut self.matrix, &mut self.basis, self.first_artificial);
+        for row in self.matrix.iter_mut() {
+            row.drain(self.first_artificial..row.len() - 1);
+        }
+        self.upper.drain(self.first_artificial..self.columns.len());
+        self.columns.truncate(self.first_artificial);
+        redundant
+    }
+}
+
+/// A problem rewritten by [`Simplex::standard_form`] over columns bounded as
+/// `0 ≤ x' ≤ u'`.
+#[derive(Debug)]
+pub(crate) struct StandardForm<T> {
+    pub(crate) problem: Simplex<T>,
+    /// Each variable as `shift + Σ coefficient · column` over the columns of
+    /// `problem`.
+    substitutions: Vec<(T, Vec<(usize, T)>)>,
+    /// Constant the shifts add to the target.
+    pub(crate) offset: T,
+}
+
+impl<T: Scalar> StandardForm<T> {
+    /// Value of each variable at the point `columns` of the standard form.
+    pub(crate) fn point(&self, columns: &[T]) -> Vec<T> {
+        self.substitutions
+            .iter()
+            .map(|(shift, terms)| {
+                terms.iter().fold(shift.clone(), |sum, (k, c)| {
+                    sum + c.clone() * columns[*k].clone()
+                })
+            })
+            .collect()
+    }
+
+    /// Change of each variable along the direction `columns` of the standard
+    /// form.
+    pub(crate) fn direction(&self, columns: &[T]) -> Vec<T> {
+        self.substitutions
+            .iter()
+            .map(|(_, terms)| {
+                terms.iter().fold(T::zero(), |sum, (k, c)| {
+                    sum + c.clone() * columns[*k].clone()
+                })
+            })
+            .collect()
+    }
+}
+
+/// Where phase 2 of [`Simplex::two_phase`] stopped.
+struct PhaseTwo<T> {
+    outcome: PivotOutcome,
+    tableau: Tableau<T>,
+    iterations: usize,
+    /// Artificial columns left basic in redundant rows, whose rows were
+    /// dropped.
+    redundant: Vec<usize>,
+}
+
+/// Upper bounds of the tableau columns, for the bounded-variable simplex.
+///
+/// A column at its upper bound is complemented: it stands for `u - x`
+/// instead of `x`, so every non-basic column still sits at zero and the
+/// tableau reads like an ordinary one.
+#[derive(Debug, Clone)]
+struct UpperBounds<T> {
+    bounds: Vec<Option<T>>,
+    complemented: Vec<bool>,
+}
+
+impl<T: Scalar> UpperBounds<T> {
+    fn new(bounds: Vec<Option<T>>) -> Self {
+        let complemented = vec![false; bounds.len()];
+        Self {
+            bounds,
+            complemented,
+        }
+    }
+
+    fn map<U>(&self, f: impl Fn(T) -> U) -> UpperBounds<U> {
+        UpperBounds {
+            bounds: self.bounds.iter().map(|u| u.clone().map(&f)).collect(),
+            complemented: self.complemented.clone(),
+        }
+    }
+
+    /// Drops the columns in `range`, as phase 1 does with the artificials.
+    fn drain(&mut self, range: std::ops::Range<usize>) {
+        self.bounds.drain(range.clone());
+        self.complemented.drain(range);
+    }
+}
+
+impl<T: Scalar> Simplex<T> {
+    /// Solves the problem, obtaining the starting basis with the configured
+    /// [`StartMethod`].
+    pub fn run_simplex(&self) -> SolveResult<T> {
+        self.solve(&mut Tracer::new(false))
+    }
+
+    /// Solves the problem like [`Simplex::run_simplex`], also returning every
+    /// tableau the solver went through and the pivot it took on each.
+    pub fn run_traced(&self) -> (SolveResult<T>, Vec<Iteration<T>>) {
+        let mut tracer = Tracer::new(true);
+        let result = self.solve(&mut tracer);
+        (result, tracer.take())
+    }
+
+    fn solve(&self, tracer: &mut Tracer<T>) -> SolveResult<T> {
+        if let Some(bounded) = self.with_binary_bounds() {
+            return bounded.solve(tracer);
+        }
+        if !self.bounds.iter().all(Bounds::is_standard) {
+            return self.run_standard_form(tracer);
+        }
+
+        match (self.algorithm, self.start_method) {
+            (Algorithm::Revised, _) => self.run_revised(tracer),
+            (Algorithm::Dual, _) => self.run_dual(tracer),
+            (Algorithm::Tableau, StartMethod::TwoPhase) => self.run_two_phase(tracer),
+            (Algorithm::Tableau, StartMethod::BigM) => self.run_big_m(tracer),
+        }
+    }
 
-        for (i, row) in self.constraints.iter().enumerate() {
-            // web_sys::console::log_1(&format!("row {} {}", i, self.constraints.len()).into());
-            for (j, col) in row.iter().take(self.variables.len()).enumerate() {
-                matrix[i][j] = *col;
+    /// Solves the problem with every variable replaced by ones bounded as
+    /// `0 ≤ x' ≤ u'`, as described in [`Bounds`], then maps the solution back.
+    fn run_standard_form(&self, tracer: &mut Tracer<T>) -> SolveResult<T> {
+        let form = match self.standard_form() {
+            Ok(form) => form,
+            Err(infeasible) => return *infeasible,
+        };
+        let offset = form.offset.clone();
+        let original = |values: Vec<(String, T)>| -> Vec<(String, T)> {
+            let columns: Vec<T> = values.into_iter().map(|(_, v)| v).collect();
+            self.variables
+                .iter()
+                .cloned()
+                .zip(form.point(&columns))
+                .collect()
+        };
+
+        match form.problem.solve(tracer) {
+            SolveResult::Optimal {
+                iterations,
+                objective,
+                values,
+                sensitivity,
+            } => {
+                // Rows map one to one, only shifted, so their dual values
+                // and ranges carry over.
+                let values = original(values);
+                let rhs_ranges = sensitivity
+                    .rhs_ranges
+                    .into_iter()
+                    .zip(self.rhs.iter())
+                    .map(|(range, rhs)| AllowableRange {
+                        value: rhs.clone(),
+                        ..range
+                    })
+                    .collect();
+                let objective_ranges = if sensitivity.objective_ranges.is_empty() {
+                    vec![]
+                } else {
+                    self.substituted_ranges(&sensitivity.objective_ranges, &form.substitutions)
+                };
+                let dual = Sensitivity {
+                    shadow_prices: sensitivity.shadow_prices,
+                    objective_ranges,
+                    rhs_ranges,
+                    alternative_optima: sensitivity.alternative_optima,
+                    ..Sensitivity::default()
+                };
+                SolveResult::Optimal {
+                    iterations,
+                    objective: objective + offset,
+                    sensitivity: self.sensitivity(dual, &values),
+                    values,
+                }
+            }
+            SolveResult::IterationLimit {
+                iterations,
+                objective,
+                values,
+            } => SolveResult::IterationLimit {
+                iterations,
+                objective: objective + offset,
+                values: original(values),
+            },
+            result => result,
+        }
+    }
+
+    /// The problem with every variable replaced by ones bounded as
+    /// `0 ≤ x' ≤ u'`, as described in [`Bounds`], or the result if some
+    /// bounds cross.
+    ///
+    /// Substituted columns are named after the variable, and `-x` for the
+    /// columns standing for its negative part, so an unbounded column still
+    /// reads naturally.
+    pub(crate) fn standard_form(&self) -> Result<StandardForm<T>, Box<SolveResult<T>>> {
+        let mut substitutions: Vec<(T, Vec<(usize, T)>)> = vec![];
+        let mut standard = Simplex {
+            variables: vec![],
+            bounds: vec![],
+            kinds: vec![],
+            constraints: CsrMatrix::default(),
+            rhs: vec![],
+            relations: vec![],
+            target: vec![],
+            ..self.clone()
+        };
+        for (variable, bounds) in self.variables.iter().zip(self.bounds.iter()) {
+            let mut column = |name: String, coefficient: T, upper: Option<T>| {
+                standard.add_variable(name, VariableKind::Continuous);
+                let k = standard.variables.len() - 1;
+                standard.bounds[k].upper = upper;
+                (k, coefficient)
+            };
+            substitutions.push(match (&bounds.lower, &bounds.upper) {
+                (Some(lower), Some(upper)) if upper.clone() < lower.clone() => {
+                    log(&format!("Bounds of {} cross", variable));
+                    return Err(Box::new(SolveResult::Infeasible {
+                        iterations: 0,
+                        infeasibility: lower.clone() - upper.clone(),
+                    }));
+                }
+                (Some(lower), upper) => (
+                    lower.clone(),
+                    vec![column(
+                        variable.clone(),
+                        T::one(),
+                        upper.clone().map(|u| u - lower.clone()),
+                    )],
+                ),
+                (None, Some(upper)) => (
+                    upper.clone(),
+                    vec![column(format!("-{}", variable), -T::one(), None)],
+                ),
+                (None, None) => (
+                    T::zero(),
+                    vec![
+                        column(variable.clone(), T::one(), None),
+                        column(format!("-{}", variable), -T::one(), None),
+                    ],
+                ),
+            });
+        }
+
+        // Rewrites the terms `a_j x_j` over the standard columns, returning
+        // them with the constant the shifts contribute.
+        let substitute = |terms: Vec<(usize, T)>| {
+            let mut constant = T::zero();
+            let mut entries = vec![];
+            for (j, a) in terms {
+                let Some((shift, columns)) = substitutions.get(j) else {
+                    continue;
+                };
+                constant = constant + a.clone() * shift.clone();
+                for (k, coefficient) in columns {
+                    entries.push((*k, a.clone() * coefficient.clone()));
+                }
+            }
+            (entries, constant)
+        };
+
+        for (i, relation) in self.relations.iter().enumerate() {
+            let terms = self
+                .constraints
+                .row(i)
+                .map(|(j, a)| (j, a.clone()))
+                .collect();
+            let (entries, constant) = substitute(terms);
+            standard.add_sparse_constraint(entries, *relation, self.rhs[i].clone() - constant);
+        }
+        let target_len = self.target.len().min(self.variables.len());
+        let (entries, offset) = substitute(
+            self.target[..target_len]
+                .iter()
+                .cloned()
+                .enumerate()
+                .collect(),
+        );
+        let mut target = vec![T::zero(); standard.variables.len() + 1];
+        for (k, a) in entries {
+            target[k] = target[k].clone() + a;
+        }
+        standard.set_target(target);
+
+        Ok(StandardForm {
+            problem: standard,
+            substitutions,
+            offset,
+        })
+    }
+
+    /// Ranges of the objective coefficients, given those of the columns of
+    /// [`Simplex::standard_form`] and how each variable was substituted.
+    /// A coefficient moves the cost of every column of its variable, so its
+    /// range is the tightest one they allow.
+    fn substituted_ranges(
+        &self,
+        ranges: &[AllowableRange<T>],
+        substitutions: &[(T, Vec<(usize, T)>)],
+    ) -> Vec<AllowableRange<T>> {
+        substitutions
+            .iter()
+            .enumerate()
+            .map(|(j, (_, columns))| {
+                let mut range = AllowableRange {
+                    value: self.target.get(j).cloned().unwrap_or(T::zero()),
+                    increase: None,
+                    decrease: None,
+                };
+                for (k, coefficient) in columns {
+                    let (increase, decrease) = if coefficient.is_negative() {
+                        (&ranges[*k].decrease, &ranges[*k].increase)
+                    } else {
+                        (&ranges[*k].increase, &ranges[*k].decrease)
+                    };
+                    if let Some(increase) = increase {
+                        tighten(&mut range.increase, increase.clone());
+                    }
+                    if let Some(decrease) = decrease {
+                        tighten(&mut range.decrease, decrease.clone());
+                    }
+                }
+                range
+            })
+            .collect()
+    }
+
+    /// Two-phase method.
+    ///
+    /// Phase 1 minimizes the sum of the artificial variables added for `≥` and
+    /// `=` rows. If it cannot be brought to zero the problem is infeasible.
+    /// Otherwise the remaining artificials are driven out of the basis, their
+    /// columns are dropped and phase 2 optimizes the real target from that
+    /// feasible basis.
+    fn run_two_phase(&self, tracer: &mut Tracer<T>) -> SolveResult<T> {
+        let start = self.initial_columns();
+        let canonical = self.canonical(&start);
+        let PhaseTwo {
+            outcome,
+            tableau:
+                Tableau {
+                    matrix,
+                    basis,
+                    columns,
+                    upper,
+                    ..
+                },
+            iterations,
+            redundant,
+        } = match self.two_phase(start, tracer) {
+            Ok(phase_two) => phase_two,
+            Err(result) => return *result,
+        };
+
+        // The artificials of redundant rows stay basic at zero in the full
+        // problem, completing the basis the dual values are read from.
+        let full_basis: Vec<usize> = basis.iter().chain(redundant.iter()).copied().collect();
+        let values = rhs(&matrix);
+        self.result(
+            outcome,
+            &values,
+            self.solution(&basis, &values, &upper.complemented),
+            &columns,
+            iterations,
+            || self.basis_sensitivity(&canonical, &full_basis, &upper.complemented),
+        )
+    }
+
+    /// Final tableau of the two-phase method, if the problem has an optimal
+    /// solution and every variable is `x ≥ 0` without an upper bound, so no
+    /// column is complemented. The artificial columns are gone.
+    pub(crate) fn optimal_tableau(&self) -> Option<Tableau<T>> {
+        let standard = self
+            .bounds
+            .iter()
+            .all(|b| b.is_standard() && b.upper.is_none());
+        match self.two_phase(self.initial_columns(), &mut Tracer::new(false)) {
+            Ok(PhaseTwo {
+                outcome: PivotOutcome::Optimal,
+                tableau,
+                ..
+            }) if standard => Some(tableau),
+            _ => None,
+        }
+    }
+
+    /// Both phases of [`Simplex::run_two_phase`] from the tableau of `start`,
+    /// or its result if phase 1 already settles the problem.
+    fn two_phase(
+        &self,
+        start: InitialColumns<T>,
+        tracer: &mut Tracer<T>,
+    ) -> Result<PhaseTwo<T>, Box<SolveResult<T>>> {
+        let mut tableau = start.tableau();
+        let mut iterations = 0;
+        let mut redundant = vec![];
+
+        if tableau.has_artificials() {
+            tableau.set_phase_one_objective();
+            tracer.begin(Stage::PhaseOne, &tableau.columns, &tableau.upper.bounds);
+            let outcome = optimize(
+                &mut tableau.matrix,
+                &mut tableau.basis,
+                &mut tableau.upper,
+                &mut iterations,
+                self.pivoting(),
+                tracer,
+            );
+            if let PivotOutcome::IterationLimit = outcome {
+                let values = rhs(&tableau.matrix);
+                return Err(Box::new(self.result(
+                    outcome,
+                    &values,
+                    self.solution(&tableau.basis, &values, &tableau.upper.complemented),
+                    &tableau.columns,
+                    iterations,
+                    Sensitivity::default,
+                )));
+            }
+
+            let infeasibility = tableau.infeasibility();
+            if infeasibility.is_positive() {
+                return Err(Box::new(SolveResult::Infeasible {
+                    iterations,
+                    infeasibility,
+                }));
+            }
+
+            redundant = tableau.drop_artificials();
+        }
+
+        self.set_phase_two_objective(&mut tableau);
+        tracer.begin(Stage::PhaseTwo, &tableau.columns, &tableau.upper.bounds);
+        let outcome = optimize(
+            &mut tableau.matrix,
+            &mut tableau.basis,
+            &mut tableau.upper,
+            &mut iterations,
+            self.pivoting(),
+            tracer,
+        );
+        Ok(PhaseTwo {
+            outcome,
+            tableau,
+            iterations,
+            redundant,
+        })
+    }
+
+    /// Replaces the objective row of `tableau` with the target to maximize,
+    /// made canonical for its basis, as phase 2 starts.
+    pub(crate) fn set_phase_two_objective(&self, tableau: &mut Tableau<T>) {
+        let Tableau {
+            matrix,
+            basis,
+            columns,
+            upper,
+            ..
+        } = tableau;
+        let last = matrix.len() - 1;
+        for e in matrix[last].iter_mut() {
+            *e = T::zero();
+        }
+        for (i, t) in self.maximized_target().enumerate() {
+            matrix[last][i] = -t;
+        }
+        for j in 0..columns.len() {
+            if upper.complemented[j] {
+                complement_column(&mut matrix[last..], j, upper.bounds[j].clone().unwrap());
+            }
+        }
+        for (i, &b) in basis.iter().enumerate() {
+            let coefficient = matrix[last][b].clone();
+            if coefficient != T::zero() {
+                for j in 0..matrix[i].len() {
+                    matrix[last][j] =
+                        matrix[last][j].clone() - coefficient.clone() * matrix[i][j].clone();
+                }
+            }
+        }
+    }
+
+    /// Big-M method.
+    ///
+    /// Every artificial variable gets a cost of `-M` in the objective, kept
+    /// symbolic as a [`BigM`] value so the objective row reads like `3M - 2`
+    /// at every pivot. The problem is infeasible if an artificial variable
+    /// remains positive at the optimum.
+    fn run_big_m(&self, tracer: &mut Tracer<T>) -> SolveResult<T> {
+        let start = self.initial_columns();
+        let canonical = self.canonical(&start);
+        let Tableau {
+            matrix,
+            mut basis,
+            columns,
+            upper,
+            first_artificial,
+        } = start.tableau();
+        let mut upper = upper.map(BigM::constant);
+        let mut matrix: Vec<Vec<BigM<T>>> = matrix
+            .iter()
+            .map(|row| row.iter().map(|e| BigM::constant(e.clone())).collect())
+            .collect();
+
+        let last = matrix.len() - 1;
+        for (i, t) in self.maximized_target().enumerate() {
+            matrix[last][i] = BigM::constant(-t);
+        }
+        for j in first_artificial..matrix[last].len() - 1 {
+            matrix[last][j] = BigM::new(T::one(), T::zero());
+        }
+        for i in 0..last {
+            if basis[i] >= first_artificial {
+                for j in 0..matrix[i].len() {
+                    matrix[last][j] =
+                        matrix[last][j].clone() - BigM::new(matrix[i][j].c.clone(), T::zero());
+                }
+            }
+        }
+
+        let mut iterations = 0;
+        let mut big_m_tracer = Tracer::new(tracer.is_on());
+        big_m_tracer.begin(Stage::BigM, &columns, &upper.bounds);
+        let mut outcome = optimize(
+            &mut matrix,
+            &mut basis,
+            &mut upper,
+            &mut iterations,
+            self.pivoting(),
+            &mut big_m_tracer,
+        );
+        tracer.extend(big_m_tracer.take().into_iter().map(Iteration::split_m));
+        let mut values: Vec<T> = rhs(&matrix).into_iter().map(|v| v.c).collect();
+
+        let infeasibility = basis
+            .iter()
+            .zip(values.iter())
+            .filter(|(b, _)| **b >= first_artificial)
+            .fold(T::zero(), |sum, (_, v)| sum + v.clone());
+        match outcome {
+            PivotOutcome::Optimal if infeasibility.is_positive() => {
+                return SolveResult::Infeasible {
+                    iterations,
+                    infeasibility,
+                };
+            }
+            // A ray with artificials still positive is unbounded only if the
+            // problem is feasible at all, which only phase 1 can tell. Its
+            // tableaux are left out so the trace stays a Big M one.
+            PivotOutcome::Unbounded(_) if infeasibility.is_positive() => {
+                if let SolveResult::Infeasible {
+                    iterations: phase_1,
+                    infeasibility,
+                } = self.run_two_phase(&mut Tracer::new(false))
+                {
+                    return SolveResult::Infeasible {
+                        iterations: iterations + phase_1,
+                        infeasibility,
+                    };
+                }
+            }
+            _ => {}
+        }
+
+        // Artificials left basic at zero would carry their penalty into the
+        // dual values, so drive them out as phase 1 does and re-optimize.
+        let mut redundant = vec![];
+        if let PivotOutcome::Optimal = outcome
+            && basis.iter().any(|&b| b >= first_artificial)
+        {
+            redundant = drive_out_artificials(&mut matrix, &mut basis, first_artificial);
+            outcome = optimize(
+                &mut matrix,
+                &mut basis,
+                &mut upper,
+                &mut iterations,
+                self.pivoting(),
+                &mut big_m_tracer,
+            );
+            tracer.extend(big_m_tracer.take().into_iter().map(Iteration::split_m));
+            values = rhs(&matrix).into_iter().map(|v| v.c).collect();
+        }
+
+        let full_basis: Vec<usize> = basis.iter().chain(redundant.iter()).copied().collect();
+        self.result(
+            outcome,
+            &values,
+            self.solution(&basis, &values, &upper.complemented),
+            &columns,
+            iterations,
+            || self.basis_sensitivity(&canonical, &full_basis, &upper.complemented),
+        )
+    }
+
+    /// Dual simplex method.
+    ///
+    /// Every row is written as `≤`, an `=` row as a pair, and upper bounds as
+    /// rows of their own, so the slacks form a basis without artificial
+    /// variables. That basis may be primal infeasible, with negative
+    /// right-hand sides, but if it is dual feasible, with no negative reduced
+    /// cost, the dual simplex keeps it so while driving out the negative
+    /// right-hand sides. Otherwise the problem is handed to the two-phase
+    /// method.
+    fn run_dual(&self, tracer: &mut Tracer<T>) -> SolveResult<T> {
+        let (tableau, rows) = self.slack_tableau();
+        let canonical = self.canonical_of(
+            &tableau.matrix,
+            tableau.upper.bounds.clone(),
+            tableau.first_artificial,
+            rows,
+        );
+        let Tableau {
+            mut matrix,
+            mut basis,
+            columns,
+            upper,
+            ..
+        } = tableau;
+        if matrix.last().unwrap().iter().any(|d| d.is_negative()) {
+            log("Slack basis is not dual feasible, using the two-phase method");
+            return self.run_two_phase(tracer);
+        }
+
+        let mut iterations = 0;
+        tracer.begin(Stage::Dual, &columns, &upper.bounds);
+        let outcome = match dual_optimize(
+            &mut matrix,
+            &mut basis,
+            &mut iterations,
+            self.pivoting(),
+            tracer,
+        ) {
+            DualOutcome::Optimal => PivotOutcome::Optimal,
+            DualOutcome::IterationLimit => PivotOutcome::IterationLimit,
+            DualOutcome::Infeasible(row) => {
+                return SolveResult::Infeasible {
+                    iterations,
+                    infeasibility: T::zero() - matrix[row].last().unwrap().clone(),
+                };
+            }
+        };
+        let values = rhs(&matrix);
+        // Upper bounds are rows of their own here, so no column is ever
+        // complemented.
+        self.result(
+            outcome,
+            &values,
+            self.solution(&basis, &values, &upper.complemented),
+            &columns,
+            iterations,
+            || self.basis_sensitivity(&canonical, &basis, &upper.complemented),
+        )
+    }
+
+    /// Tableau of the problem with every constraint and upper bound as a `≤`
+    /// row and its slack basic, right-hand sides kept as they are, so it has
+    /// neither artificial nor bounded columns. Also returns the constraint
+    /// each row comes from, as in [`Canonical::rows`].
+    fn slack_tableau(&self) -> (Tableau<T>, Vec<Option<(usize, bool)>>) {
+        let n = self.variables.len();
+        let mut rows: Vec<Vec<T>> = vec![];
+        let mut origins = vec![];
+        for (k, relation) in self.relations.iter().enumerate() {
+            let mut coefficients = self.constraints.dense_row(k, n);
+            coefficients.push(self.rhs[k].clone());
+            let negated: Vec<T> = coefficients.iter().map(|c| -c.clone()).collect();
+            match relation {
+                Relation::LessEqual => {
+                    rows.push(coefficients);
+                    origins.push(Some((k, false)));
+                }
+                Relation::GreaterEqual => {
+                    rows.push(negated);
+                    origins.push(Some((k, true)));
+                }
+                Relation::Equal => {
+                    rows.push(coefficients);
+                    rows.push(negated);
+                    origins.extend([Some((k, false)), Some((k, true))]);
+                }
             }
         }
-        for i in 0..self.constraints.len() {
-            *matrix[i].last_mut().unwrap() = *self.constraints[i].last().unwrap();
+        for (j, bounds) in self.bounds.iter().enumerate() {
+            if let Some(upper) = &bounds.upper {
+                let mut coefficients = vec![T::zero(); n + 1];
+                coefficients[j] = T::one();
+                coefficients[n] = upper.clone();
+                rows.push(coefficients);
+                origins.push(None);
+            }
+        }
+
+        let width = n + rows.len() + 1;
+        let mut matrix = vec![vec![T::zero(); width]; rows.len() + 1];
+        for (i, coefficients) in rows.iter().enumerate() {
+            matrix[i][..n].clone_from_slice(&coefficients[..n]);
+            matrix[i][n + i] = T::one();
+            matrix[i][width - 1] = coefficients[n].clone();
+        }
+        let last = matrix.len() - 1;
+        for (j, t) in self.maximized_target().enumerate() {
+            matrix[last][j] = -t;
         }
 
-        for i in 0..self.constraints.len() {
-            matrix[i][i + self.variables.len()] = 1_f32;
+        let basis = (n..n + rows.len()).collect();
+        let mut columns = self.variables.clone();
+        columns.extend((1..=rows.len()).map(|i| format!("s{}", i)));
+        let tableau = Tableau {
+            matrix,
+            basis,
+            upper: UpperBounds::new(vec![None; columns.len()]),
+            first_artificial: columns.len(),
+            columns,
+        };
+        (tableau, origins)
+    }
+
+    /// Two-phase method on the [`RevisedSimplex`].
+    ///
+    /// Phase 1 maximizes minus the sum of the artificial variables as in
+    /// [`Simplex::run_two_phase`]. Instead of being driven out, artificials are
+    /// then fixed at zero, so rows that turn out redundant just keep one in
+    /// the basis.
+    ///
+    /// This is the path for large sparse models: `A` goes from the sparse
+    /// constraints to the solver column by column, and no dense tableau is
+    /// ever built.
+    fn run_revised(&self, tracer: &mut Tracer<T>) -> SolveResult<T> {
+        let start = self.initial_columns();
+        let canonical = self.canonical(&start);
+        let InitialColumns {
+            a,
+            rhs,
+            basis,
+            columns,
+            upper,
+            first_artificial,
+        } = start;
+        let width = columns.len();
+        let mut revised = RevisedSimplex::new(a, rhs, upper, basis);
+        let mut iterations = 0;
+
+        if first_artificial < width {
+            let costs: Vec<T> = (0..width)
+                .map(|j| {
+                    if j < first_artificial {
+                        T::zero()
+                    } else {
+                        -T::one()
+                    }
+                })
+                .collect();
+            tracer.begin(Stage::PhaseOne, &columns, revised.upper());
+            let outcome = revised.optimize(&costs, width, self.pivoting(), &mut iterations, tracer);
+            if let PivotOutcome::IterationLimit = outcome {
+                return self.revised_result(
+                    outcome,
+                    &revised,
+                    &columns,
+                    iterations,
+                    Sensitivity::default,
+                );
+            }
+
+            let infeasibility = -revised.objective(&costs);
+            if infeasibility.is_positive() {
+                return SolveResult::Infeasible {
+                    iterations,
+                    infeasibility,
+                };
+            }
+            revised.fix_from(first_artificial);
         }
 
-        for (i, t) in self.target.iter().take(self.target.len() - 1).enumerate() {
-            matrix.last_mut().unwrap()[i] = *t * -1_f32;
+        let costs = self.costs(width);
+        tracer.begin(Stage::PhaseTwo, &columns, revised.upper());
+        let outcome = revised.optimize(
+            &costs,
+            first_artificial,
+            self.pivoting(),
+            &mut iterations,
+            tracer,
+        );
+        self.revised_result(outcome, &revised, &columns, iterations, || {
+            self.basis_sensitivity(&canonical, revised.basis(), revised.at_upper())
+        })
+    }
+
+    /// Turns the state a [`RevisedSimplex`] stopped in into a [`SolveResult`].
+    fn revised_result(
+        &self,
+        outcome: PivotOutcome,
+        revised: &RevisedSimplex<T>,
+        columns: &[String],
+        iterations: usize,
+        dual: impl FnOnce() -> Sensitivity<T>,
+    ) -> SolveResult<T> {
+        let values: Vec<(String, T)> = self
+            .variables
+            .iter()
+            .cloned()
+            .zip(revised.values())
+            .collect();
+        let objective = self.objective(
+            self.maximized_target()
+                .zip(values.iter())
+                .fold(T::zero(), |sum, (c, (_, x))| sum + c * x.clone()),
+        );
+        match outcome {
+            PivotOutcome::Optimal => SolveResult::Optimal {
+                iterations,
+                objective,
+                sensitivity: self.sensitivity(dual(), &values),
+                values,
+            },
+            PivotOutcome::Unbounded(column) => {
+                log("Problem is unbounded");
+                SolveResult::Unbounded {
+                    iterations,
+                    variable: columns[column].clone(),
+                }
+            }
+            PivotOutcome::IterationLimit => {
+                log("Iteration limit reached");
+                SolveResult::IterationLimit {
+                    iterations,
+                    objective,
+                    values,
+                }
+            }
+        }
+    }
+
+    /// Coefficients of the target as a function to maximize: the target
+    /// itself, or its negation when minimizing.
+    fn maximized_target(&self) -> impl Iterator<Item = T> + '_ {
+        self.target
+            .iter()
+            .take(self.variables.len())
+            .map(|t| match self.objective_sense {
+                ObjectiveSense::Maximize => t.clone(),
+                ObjectiveSense::Minimize => -t.clone(),
+            })
+    }
+
+    /// Value of the target given the value of [`Simplex::maximized_target`].
+    fn objective(&self, maximized: T) -> T {
+        match self.objective_sense {
+            ObjectiveSense::Maximize => maximized,
+            ObjectiveSense::Minimize => -maximized,
+        }
+    }
+
+    fn pivoting(&self) -> Pivoting {
+        Pivoting {
+            pricing_rule: self.pricing_rule,
+            anti_cycling: self.anti_cycling,
+            max_iterations: self.max_iterations,
+        }
+    }
+
+    /// Turns the state [`optimize`] stopped in into a [`SolveResult`], given
+    /// the right-hand side of every row, the [`Simplex::solution`] it
+    /// describes and what the basis says about the constraints, computed
+    /// only if it is optimal.
+    fn result(
+        &self,
+        outcome: PivotOutcome,
+        values: &[T],
+        solution: Vec<(String, T)>,
+        columns: &[String],
+        iterations: usize,
+        dual: impl FnOnce() -> Sensitivity<T>,
+    ) -> SolveResult<T> {
+        match outcome {
+            PivotOutcome::Optimal => SolveResult::Optimal {
+                iterations,
+                objective: self.objective(values.last().unwrap().clone()),
+                sensitivity: self.sensitivity(dual(), &solution),
+                values: solution,
+            },
+            PivotOutcome::Unbounded(column) => {
+                log("Problem is unbounded");
+                SolveResult::Unbounded {
+                    iterations,
+                    variable: columns[column].clone(),
+                }
+            }
+            PivotOutcome::IterationLimit => {
+                log("Iteration limit reached");
+                SolveResult::IterationLimit {
+                    iterations,
+                    objective: self.objective(values.last().unwrap().clone()),
+                    values: solution,
+                }
+            }
+        }
+    }
+
+    /// The initial columns as a [`Canonical`] problem, with the artificial
+    /// columns fixed at zero.
+    fn canonical(&self, start: &InitialColumns<T>) -> Canonical<T> {
+        let mut upper = start.upper.clone();
+        for u in upper.iter_mut().skip(start.first_artificial) {
+            *u = Some(T::zero());
+        }
+        let rows = self
+            .rhs
+            .iter()
+            .enumerate()
+            .map(|(i, b)| Some((i, b.is_negative())))
+            .collect();
+        Canonical {
+            columns: start.a.clone(),
+            rhs: start.rhs.clone(),
+            costs: self.costs(start.columns.len()),
+            upper,
+            eligible: start.first_artificial,
+            rows,
+        }
+    }
+
+    /// A [`Canonical`] problem over the columns of a starting `matrix`, whose
+    /// last row and column are the objective and the right-hand side.
+    fn canonical_of(
+        &self,
+        matrix: &[Vec<T>],
+        upper: Vec<Option<T>>,
+        eligible: usize,
+        rows: Vec<Option<(usize, bool)>>,
+    ) -> Canonical<T> {
+        let m = matrix.len() - 1;
+        let width = matrix[0].len() - 1;
+        Canonical {
+            columns: CscMatrix::from_dense_rows(&matrix[..m], width),
+            rhs: (0..m).map(|i| matrix[i][width].clone()).collect(),
+            costs: self.costs(width),
+            upper,
+            eligible,
+            rows,
+        }
+    }
+
+    /// Cost of each of `width` columns in the maximized target, zero past
+    /// the decision variables.
+    fn costs(&self, width: usize) -> Vec<T> {
+        let mut costs = vec![T::zero(); width];
+        for (c, t) in costs.iter_mut().zip(self.maximized_target()) {
+            *c = t;
+        }
+        costs
+    }
+
+    /// Shadow prices and ranges of an optimal basis of `canonical`, given as
+    /// the basic column of each row, in terms of the target as entered.
+    fn basis_sensitivity(
+        &self,
+        canonical: &Canonical<T>,
+        basis: &[usize],
+        at_upper: &[bool],
+    ) -> Sensitivity<T> {
+        let Some(analysis) = canonical.analyze(basis, at_upper, self.rhs.len()) else {
+            return Sensitivity::default();
+        };
+
+        let objective_ranges = analysis
+            .cost_ranges
+            .into_iter()
+            .take(self.variables.len())
+            .enumerate()
+            .map(|(j, (increase, decrease))| {
+                // Raising a coefficient of a minimized target lowers the
+                // maximized one.
+                let (increase, decrease) = match self.objective_sense {
+                    ObjectiveSense::Maximize => (increase, decrease),
+                    ObjectiveSense::Minimize => (decrease, increase),
+                };
+                AllowableRange {
+                    value: self.target.get(j).cloned().unwrap_or(T::zero()),
+                    increase,
+                    decrease,
+                }
+            })
+            .collect();
+        let rhs_ranges = analysis
+            .rhs_ranges
+            .into_iter()
+            .zip(self.rhs.iter())
+            .map(|((increase, decrease), rhs)| AllowableRange {
+                value: rhs.clone(),
+                increase,
+                decrease,
+            })
+            .collect();
+
+        Sensitivity {
+            shadow_prices: analysis
+                .prices
+                .into_iter()
+                .map(|p| self.objective(p))
+                .collect(),
+            objective_ranges,
+            rhs_ranges,
+            alternative_optima: analysis.alternative,
+            ..Sensitivity::default()
+        }
+    }
+
+    /// Completes `dual`, which holds the shadow prices and ranges, with the
+    /// slacks, binding constraints and reduced costs at the optimal point
+    /// `values`.
+    fn sensitivity(&self, dual: Sensitivity<T>, values: &[(String, T)]) -> Sensitivity<T> {
+        let shadow_prices = dual.shadow_prices;
+        let x: Vec<T> = values.iter().map(|(_, x)| x.clone()).collect();
+        let mut slacks = vec![];
+        for (i, (rhs, relation)) in self.rhs.iter().zip(self.relations.iter()).enumerate() {
+            let lhs = self.constraints.dot_row(i, &x);
+            slacks.push(match relation {
+                Relation::LessEqual => rhs.clone() - lhs,
+                Relation::GreaterEqual => lhs - rhs.clone(),
+                Relation::Equal => T::zero(),
+            });
+        }
+        let binding = slacks.iter().map(|s| s.is_zero()).collect();
+
+        let reduced_costs = if shadow_prices.len() == self.rhs.len() {
+            let n = self.variables.len();
+            let mut reduced: Vec<T> = (0..n)
+                .map(|j| self.target.get(j).cloned().unwrap_or(T::zero()))
+                .collect();
+            for (i, y) in shadow_prices.iter().enumerate() {
+                for (j, a) in self.constraints.row(i).filter(|(j, _)| *j < n) {
+                    reduced[j] = reduced[j].clone() - y.clone() * a.clone();
+                }
+            }
+            self.variables.iter().cloned().zip(reduced).collect()
+        } else {
+            vec![]
+        };
+
+        Sensitivity {
+            shadow_prices,
+            reduced_costs,
+            slacks,
+            binding,
+            objective_ranges: dual.objective_ranges,
+            rhs_ranges: dual.rhs_ranges,
+            alternative_optima: dual.alternative_optima,
+        }
+    }
+
+    /// Value of every decision variable, given the basic column of each row,
+    /// the right-hand side of each row and which columns are complemented.
+    fn solution(&self, basis: &[usize], values: &[T], complemented: &[bool]) -> Vec<(String, T)> {
+        let mut solution: HashMap<String, T> = self
+            .variables
+            .iter()
+            .map(|v| (v.clone(), T::zero()))
+            .collect();
+        for (i, &b) in basis.iter().enumerate() {
+            if b < self.variables.len() {
+                solution.insert(self.variables[b].clone(), values[i].clone());
+            }
+        }
+
+        self.variables
+            .iter()
+            .zip(self.bounds.iter())
+            .enumerate()
+            .map(|(j, (v, bounds))| {
+                let value = solution.get(v).unwrap().clone();
+                match &bounds.upper {
+                    Some(upper) if complemented[j] => (v.clone(), upper.clone() - value),
+                    _ => (v.clone(), value),
+                }
+            })
+            .collect()
+    }
+
+    /// Builds the starting tableau. Columns are laid out as decision variables,
+    /// one slack/surplus per inequality (`s1`, `s2`, …), one artificial per
+    /// `≥`/`=` row (`a1`, `a2`, …) and the right-hand side. Rows with a
+    /// negative right-hand side are negated first.
+    pub(crate) fn initial_tableau(&self) -> Tableau<T> {
+        self.initial_columns().tableau()
+    }
+
+    /// The columns and starting basis of [`Simplex::initial_tableau`], built
+    /// straight from the sparse constraints without the dense tableau.
+    fn initial_columns(&self) -> InitialColumns<T> {
+        let n = self.variables.len();
+        let negated: Vec<bool> = self.rhs.iter().map(|b| b.is_negative()).collect();
+        let relations: Vec<Relation> = self
+            .relations
+            .iter()
+            .zip(negated.iter())
+            .map(|(relation, &negated)| if negated { relation.flip() } else { *relation })
+            .collect();
+        let rhs = self
+            .rhs
+            .iter()
+            .zip(negated.iter())
+            .map(|(b, &negated)| if negated { -b.clone() } else { b.clone() })
+            .collect();
+
+        let structural = self.constraints.to_csc(n);
+        let mut a = CscMatrix::new(relations.len());
+        for j in 0..n {
+            a.push_column(
+                structural
+                    .column(j)
+                    .map(|(i, c)| (i, if negated[i] { -c.clone() } else { c.clone() })),
+            );
+        }
+
+        let slacks = relations.iter().filter(|r| **r != Relation::Equal).count();
+        let first_artificial = n + slacks;
+        let mut basis = vec![0; relations.len()];
+        for (i, relation) in relations.iter().enumerate() {
+            match relation {
+                Relation::LessEqual => {
+                    basis[i] = a.columns();
+                    a.push_column([(i, T::one())]);
+                }
+                Relation::GreaterEqual => a.push_column([(i, -T::one())]),
+                Relation::Equal => {}
+            }
+        }
+        for (i, relation) in relations.iter().enumerate() {
+            if *relation != Relation::LessEqual {
+                basis[i] = a.columns();
+                a.push_column([(i, T::one())]);
+            }
         }
-        // *matrix.last_mut().unwrap().last_mut().unwrap() = *self.target.last().unwrap() * -1_f32;
-        // matrix.last_mut().unwrap()[0] = 1_f32;
 
-        // let mut basic_variables = self.variables.clone();
-        print_matrix(&matrix);
+        let mut columns = self.variables.clone();
+        columns.extend((1..=slacks).map(|i| format!("s{}", i)));
+        columns.extend((1..=a.columns() - first_artificial).map(|i| format!("a{}", i)));
 
-        let mut solution: HashMap<String, f32> = HashMap::new();
+        let mut upper: Vec<Option<T>> = self.bounds.iter().map(|b| b.upper.clone()).collect();
+        upper.resize(columns.len(), None);
 
-        let mut basic_variables = vec![VariableType::Basic; self.constraints.len()];
-        let mut non_basic_variables =
-            vec![VariableType::Basic; self.variables.len() + self.constraints.len() + 1];
-        for (i, v) in self.variables.iter().enumerate() {
-            non_basic_variables[i] = VariableType::NonBasic(v.clone());
-            solution.insert(v.clone(), 0_f32);
-            // web_sys::console::log_1(&format!("asdasdasd").into());
+        InitialColumns {
+            a,
+            rhs,
+            basis,
+            columns,
+            upper,
+            first_artificial,
         }
+    }
 
-        loop {
-            print_matrix(&matrix);
-
-            let pivot_column = get_pivot_column(&matrix);
-            let pivot_row = get_pivot_row(&matrix, pivot_column);
+    /// The dual of the problem, with a variable `y1`, `y2`, … for every
+    /// constraint and a constraint for every decision variable.
+    ///
+    /// Bounds other than a sign restriction become constraints of the primal
+    /// first, so their dual variables follow those of the constraints. Both
+    /// problems have the same optimal value, and the optimal dual variables
+    /// are the shadow prices of the primal rows.
+    pub fn dual(&self) -> Simplex<T> {
+        if let Some(bounded) = self.with_binary_bounds() {
+            return bounded.dual();
+        }
+        let n = self.variables.len();
 
-            web_sys::console::log_1(
-                &format!("Pivot row: {}\nPivot col: {}\n", pivot_row, pivot_column).into(),
+        // Every row of the primal as its coefficients, and its relation and
+        // right-hand side, and the sign restriction left on each variable.
+        let mut coefficients = CsrMatrix::default();
+        let mut rows: Vec<(Relation, T)> = vec![];
+        for (i, (relation, rhs)) in self.relations.iter().zip(self.rhs.iter()).enumerate() {
+            coefficients.push_row(
+                self.constraints
+                    .row(i)
+                    .filter(|(j, _)| *j < n)
+                    .map(|(j, a)| (j, a.clone()))
+                    .collect(),
             );
-            let mut exit = true;
-
-            for e in matrix.last().unwrap() {
-                if *e < 0_f32 {
-                    exit = false;
-                }
+            rows.push((*relation, rhs.clone()));
+        }
+        let mut signs: Vec<VariableSign<T>> = vec![];
+        for (j, bounds) in self.bounds.iter().enumerate() {
+            let sign = if bounds.lower.as_ref().is_some_and(|l| l.is_zero()) {
+                VariableSign::NonNegative
+            } else if bounds.upper.as_ref().is_some_and(|u| u.is_zero()) {
+                VariableSign::NonPositive
+            } else {
+                VariableSign::Free
+            };
+            if let Some(lower) = &bounds.lower
+                && sign != VariableSign::NonNegative
+            {
+                coefficients.push_row(vec![(j, T::one())]);
+                rows.push((Relation::GreaterEqual, lower.clone()));
             }
-            if exit {
-                break;
+            if let Some(upper) = &bounds.upper
+                && sign != VariableSign::NonPositive
+            {
+                coefficients.push_row(vec![(j, T::one())]);
+                rows.push((Relation::LessEqual, upper.clone()));
             }
+            signs.push(sign);
+        }
 
-            web_sys::console::log_1(&format!("basic_vars {:?}", basic_variables).into());
-            web_sys::console::log_1(&format!("non_basic_vars {:?}", non_basic_variables).into());
+        // The relation whose dual variable is non-negative, and the one a
+        // non-negative variable gets in the dual.
+        let (natural, dual_natural, objective_sense) = match self.objective_sense {
+            ObjectiveSense::Maximize => (
+                Relation::LessEqual,
+                Relation::GreaterEqual,
+                ObjectiveSense::Minimize,
+            ),
+            ObjectiveSense::Minimize => (
+                Relation::GreaterEqual,
+                Relation::LessEqual,
+                ObjectiveSense::Maximize,
+            ),
+        };
 
-            let temp = basic_variables[pivot_row].clone();
-            basic_variables[pivot_row] = non_basic_variables[pivot_column].clone();
-            non_basic_variables[pivot_column] = temp;
+        let mut dual = Simplex {
+            variables: vec![],
+            bounds: vec![],
+            kinds: vec![],
+            constraints: CsrMatrix::default(),
+            rhs: vec![],
+            relations: vec![],
+            target: vec![],
+            objective_sense,
+            ..self.clone()
+        };
+        for (i, (relation, _)) in rows.iter().enumerate() {
+            dual.add_variable(format!("y{}", i + 1), VariableKind::Continuous);
+            let sign = match relation {
+                Relation::Equal => VariableSign::Free,
+                relation if *relation == natural => VariableSign::NonNegative,
+                _ => VariableSign::NonPositive,
+            };
+            dual.set_variable_sign(i, sign);
+        }
+        let columns = coefficients.to_csc(n);
+        for (j, sign) in signs.iter().enumerate() {
+            let relation = match sign {
+                VariableSign::NonNegative => dual_natural,
+                VariableSign::NonPositive => dual_natural.flip(),
+                _ => Relation::Equal,
+            };
+            dual.add_sparse_constraint(
+                columns.column(j).map(|(i, a)| (i, a.clone())).collect(),
+                relation,
+                self.target.get(j).cloned().unwrap_or(T::zero()),
+            );
+        }
+        let mut target: Vec<T> = rows.into_iter().map(|(_, b)| b).collect();
+        target.push(T::zero());
+        dual.set_target(target);
+        dual
+    }
 
-            matrix = get_new_matrix(matrix.clone(), pivot_column, pivot_row);
+    /// The problem with the implicit `0 ≤ x ≤ 1` of every binary variable
+    /// added to its bounds, or `None` if its bounds are that tight already.
+    pub(crate) fn with_binary_bounds(&self) -> Option<Simplex<T>> {
+        let mut bounded = self.clone();
+        let mut tightened = false;
+        for (bounds, kind) in bounded.bounds.iter_mut().zip(self.kinds.iter()) {
+            if *kind != VariableKind::Binary {
+                continue;
+            }
+            if bounds.lower.as_ref().is_none_or(|l| l.is_negative()) {
+                bounds.lower = Some(T::zero());
+                tightened = true;
+            }
+            if bounds.upper.as_ref().is_none_or(|u| *u > T::one()) {
+                bounds.upper = Some(T::one());
+                tightened = true;
+            }
         }
+        tightened.then_some(bounded)
+    }
 
-        for (i, v) in basic_variables.iter().enumerate() {
-            if let VariableType::NonBasic(s) = v {
-                let sol = *matrix[i].last().unwrap();
-                web_sys::console::log_1(&format!("sol {}", sol).into());
-                solution.insert(s.clone(), sol);
+    /// The problem with every upper bound written as a `≤` constraint, so
+    /// every vertex is a basis of its tableau.
+    pub(crate) fn with_upper_bound_rows(&self) -> Simplex<T> {
+        let mut rows = self.clone();
+        for (j, b) in self.bounds.iter().enumerate() {
+            if let Some(upper) = &b.upper {
+                rows.set_bounds(j, Bounds::default());
+                rows.add_sparse_constraint(vec![(j, T::one())], Relation::LessEqual, upper.clone());
             }
         }
+        rows
+    }
+
+    /// The 0-1 knapsack problem: pick the items of largest total value whose
+    /// total weight fits in `capacity`, with a binary variable `x1`, `x2`, …
+    /// for each item.
+    ///
+    /// # Panics
+    ///
+    /// If `weights` and `values` have different lengths.
+    pub fn knapsack(weights: &[T], values: &[T], capacity: T) -> Simplex<T> {
+        assert_eq!(
+            weights.len(),
+            values.len(),
+            "every item needs a weight and a value"
+        );
+        let mut knapsack = Simplex::default();
+        for i in 1..=weights.len() {
+            knapsack.add_variable(format!("x{}", i), VariableKind::Binary);
+        }
+        let mut constraint = weights.to_vec();
+        constraint.push(capacity);
+        knapsack.add_constraint(constraint, Relation::LessEqual);
+        let mut target = values.to_vec();
+        target.push(T::zero());
+        knapsack.set_target(target);
+        knapsack.set_objective_sense(ObjectiveSense::Maximize);
+        knapsack
+    }
 
-        (
-            *matrix.last().unwrap().last().unwrap(),
-            self.variables
-                .iter()
-                .map(|v| (v.clone(), *solution.get(v).unwrap()))
-                .collect(),
-        )
+    /// Adds a constraint given as the coefficient of every variable followed
+    /// by its right-hand side.
+    pub fn add_constraint(&mut self, mut constraint: Vec<T>, relation: Relation) {
+        let rhs = constraint.pop().unwrap_or(T::zero());
+        self.constraints.push_dense_row(&constraint);
+        self.rhs.push(rhs);
+        self.relations.push(relation);
     }
 
-    pub fn add_constraint(&mut self, constraint: Vec<f32>) {
-        self.constraints.push(constraint);
+    /// Adds a constraint given only by its non-zero coefficients, as
+    /// `(variable, coefficient)` in any order, which keeps large models with
+    /// few variables per row small. Coefficients of the same variable add up.
+    pub fn add_sparse_constraint(
+        &mut self,
+        coefficients: Vec<(usize, T)>,
+        relation: Relation,
+        rhs: T,
+    ) {
+        self.constraints.push_row(coefficients);
+        self.rhs.push(rhs);
+        self.relations.push(relation);
     }
 
     pub fn clear_constraint(&mut self) {
         self.constraints.clear();
+        self.rhs.clear();
+        self.relations.clear();
     }
 
-    pub fn add_variable(&mut self, variable: String) {
+    pub fn add_variable(&mut self, variable: String, kind: VariableKind) {
         self.variables.push(variable);
+        self.bounds.push(Bounds::default());
+        self.kinds.push(kind);
     }
 
     pub fn clear_variables(&mut self) {
         self.variables.clear();
+        self.bounds.clear();
+        self.kinds.clear();
+    }
+
+    /// Sets the sign restriction of the variable at index `variable`,
+    /// replacing its bounds.
+    pub fn set_variable_sign(&mut self, variable: usize, sign: VariableSign<T>) {
+        self.bounds[variable] = sign.into();
+    }
+
+    pub fn set_bounds(&mut self, variable: usize, bounds: Bounds<T>) {
+        self.bounds[variable] = bounds;
+    }
+
+    pub fn get_bounds(&self) -> Vec<Bounds<T>> {
+        self.bounds.clone()
+    }
+
+    pub fn set_variable_kind(&mut self, variable: usize, kind: VariableKind) {
+        self.kinds[variable] = kind;
+    }
+
+    pub fn get_variable_kinds(&self) -> Vec<VariableKind> {
+        self.kinds.clone()
     }
 
-    pub fn set_target(&mut self, target: Vec<f32>) {
+    pub fn set_target(&mut self, target: Vec<T>) {
         self.target = target;
     }
 
-    pub fn get_constraints(&self) -> Vec<Vec<f32>> {
-        self.constraints.clone()
+    pub fn set_objective_sense(&mut self, objective_sense: ObjectiveSense) {
+        self.objective_sense = objective_sense;
+    }
+
+    pub fn get_objective_sense(&self) -> ObjectiveSense {
+        self.objective_sense
+    }
+
+    pub fn set_algorithm(&mut self, algorithm: Algorithm) {
+        self.algorithm = algorithm;
+    }
+
+    pub fn get_algorithm(&self) -> Algorithm {
+        self.algorithm
+    }
+
+    pub fn set_start_method(&mut self, start_method: StartMethod) {
+        self.start_method = start_method;
+    }
+
+    pub fn get_start_method(&self) -> StartMethod {
+        self.start_method
+    }
+
+    pub fn set_pricing_rule(&mut self, pricing_rule: PricingRule) {
+        self.pricing_rule = pricing_rule;
+    }
+
+    pub fn get_pricing_rule(&self) -> PricingRule {
+        self.pricing_rule
+    }
+
+    pub fn set_anti_cycling(&mut self, anti_cycling: AntiCycling) {
+        self.anti_cycling = anti_cycling;
+    }
+
+    pub fn get_anti_cycling(&self) -> AntiCycling {
+        self.anti_cycling
+    }
+
+    pub fn set_max_iterations(&mut self, max_iterations: usize) {
+        self.max_iterations = max_iterations;
+    }
+
+    pub fn get_max_iterations(&self) -> usize {
+        self.max_iterations
+    }
+
+    pub fn set_node_selection(&mut self, node_selection: NodeSelection) {
+        self.node_selection = node_selection;
+    }
+
+    pub fn get_node_selection(&self) -> NodeSelection {
+        self.node_selection
+    }
+
+    pub fn set_branching_rule(&mut self, branching_rule: BranchingRule) {
+        self.branching_rule = branching_rule;
+    }
+
+    pub fn get_branching_rule(&self) -> BranchingRule {
+        self.branching_rule
+    }
+
+    pub fn set_max_nodes(&mut self, max_nodes: usize) {
+        self.max_nodes = max_nodes;
+    }
+
+    pub fn get_max_nodes(&self) -> usize {
+        self.max_nodes
+    }
+
+    /// Every constraint as the coefficient of each variable followed by its
+    /// right-hand side.
+    pub fn get_constraints(&self) -> Vec<Vec<T>> {
+        (0..self.rhs.len())
+            .map(|i| {
+                let mut row = self.constraints.dense_row(i, self.variables.len());
+                row.push(self.rhs[i].clone());
+                row
+            })
+            .collect()
+    }
+
+    /// Coefficients of every constraint as they are stored, without the
+    /// right-hand sides.
+    pub fn get_constraint_matrix(&self) -> &CsrMatrix<T> {
+        &self.constraints
+    }
+
+    pub fn get_rhs(&self) -> Vec<T> {
+        self.rhs.clone()
+    }
+
+    pub fn get_relations(&self) -> Vec<Relation> {
+        self.relations.clone()
     }
 
     pub fn get_variables(&self) -> Vec<String> {
         self.variables.clone()
     }
 
-    pub fn get_target(&self) -> Vec<f32> {
+    pub fn get_target(&self) -> Vec<T> {
         self.target.clone()
     }
 }
 
-fn print_matrix(m: &Vec<Vec<f32>>) {
-    let mut s = String::new();
-
-    for row in m {
-        for col in row {
-            s.push_str(format!("{:.2} ", col).as_str());
+impl<T: Scalar> fmt::Display for Simplex<T> {
+    /// Writes the model one line at a time: the target, every constraint and
+    /// the bounds of the variables, like
+    ///
+    /// ```text
+    /// Max 3x + 2y
+    /// x + y ≤ 4
+    /// x ≥ 0, y free
+    /// ```
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ", self.objective_sense)?;
+        write_linear(f, &self.target, &self.variables)?;
+        for (i, (rhs, relation)) in self.rhs.iter().zip(self.relations.iter()).enumerate() {
+            writeln!(f)?;
+            let row = self.constraints.dense_row(i, self.variables.len());
+            write_linear(f, &row, &self.variables)?;
+            write!(f, " {} {}", relation, rhs)?;
         }
-        s.push_str("\n");
+        writeln!(f)?;
+        for (j, (variable, bounds)) in self.variables.iter().zip(self.bounds.iter()).enumerate() {
+            if j > 0 {
+                write!(f, ", ")?;
+            }
+            match (&bounds.lower, &bounds.upper) {
+                (Some(lower), Some(upper)) => write!(f, "{} ≤ {} ≤ {}", lower, variable, upper)?,
+                (Some(lower), None) => write!(f, "{} ≥ {}", variable, lower)?,
+                (None, Some(upper)) => write!(f, "{} ≤ {}", variable, upper)?,
+                (None, None) => write!(f, "{} free", variable)?,
+            }
+        }
+        Ok(())
     }
-    web_sys::console::log_1(&s.into());
 }
 
-fn get_pivot_column(matrix: &Vec<Vec<f32>>) -> usize {
-    let mut pivot_column = 0;
+/// Writes `Σ a_j x_j` like `3x - y + 1/2z`, leaving out zero terms.
+pub(crate) fn write_linear<T: Scalar>(
+    f: &mut fmt::Formatter<'_>,
+    coefficients: &[T],
+    variables: &[String],
+) -> fmt::Result {
+    let mut first = true;
+    for (a, variable) in coefficients.iter().zip(variables.iter()) {
+        if a.is_zero() {
+            continue;
+        }
+        if first {
+            if a.is_negative() {
+                write!(f, "-")?;
+            }
+        } else if a.is_negative() {
+            write!(f, " - ")?;
+        } else {
+            write!(f, " + ")?;
+        }
+        first = false;
+        let a = a.abs();
+        if a == T::one() {
+            write!(f, "{}", variable)?;
+        } else {
+            write!(f, "{}{}", a, variable)?;
+        }
+    }
+    if first {
+        write!(f, "0")?;
+    }
+    Ok(())
+}
 
-    for (i, e) in matrix
-        .last()
-        .unwrap()
+/// Right-hand side of every row, objective value last.
+fn rhs<T: Scalar>(matrix: &[Vec<T>]) -> Vec<T> {
+    matrix
         .iter()
-        .enumerate()
-        .take(matrix.last().unwrap().len() - 1)
+        .map(|row| row.last().unwrap().clone())
+        .collect()
+}
+
+/// Pivots until no reduced cost in the objective row is negative, nothing
+/// limits the entering column, or `iterations` reaches the limit.
+///
+/// Columns with an upper bound follow the bounded-variable simplex: the
+/// entering column may stop at its own bound, which complements it without
+/// changing the basis, and a basic variable may leave at its upper bound.
+/// The anti-cycling rules only decide ties among rows leaving at zero.
+fn optimize<T: Scalar>(
+    matrix: &mut Vec<Vec<T>>,
+    basis: &mut [usize],
+    upper: &mut UpperBounds<T>,
+    iterations: &mut usize,
+    pivoting: Pivoting,
+    tracer: &mut Tracer<T>,
+) -> PivotOutcome {
+    // The lexicographic ratio test compares rows over the columns that were
+    // basic when this phase started.
+    let reference = basis.to_vec();
+    let mut rule = pivoting.anti_cycling;
+    let mut pricer = pivoting.pricing_rule.pricer();
+    let mut seen: HashSet<(Vec<usize>, Vec<bool>)> = HashSet::new();
+
+    loop {
+        if rule == AntiCycling::Automatic {
+            let mut key = basis.to_vec();
+            key.sort_unstable();
+            if !seen.insert((key, upper.complemented.clone())) {
+                log("Basis repeated, switching to Bland's rule");
+                rule = AntiCycling::Bland;
+            }
+        }
+
+        let pivot_column = match rule {
+            AntiCycling::Bland => get_pivot_column_bland(matrix),
+            _ => {
+                let objective = matrix.last().unwrap();
+                let edges = TableauEdges {
+                    matrix,
+                    basis,
+                    upper,
+                };
+                pricer.select(&objective[..objective.len() - 1], &edges)
+            }
+        };
+        let Some(pivot_column) = pivot_column else {
+            tracer.record(matrix, basis, &upper.complemented, None, None, Vec::new);
+            return PivotOutcome::Optimal;
+        };
+        if *iterations >= pivoting.max_iterations {
+            tracer.record(matrix, basis, &upper.complemented, None, None, Vec::new);
+            return PivotOutcome::IterationLimit;
+        }
+        let pivot_row = match rule {
+            AntiCycling::Automatic => get_pivot_row(matrix, pivot_column),
+            AntiCycling::Bland => get_pivot_row_bland(matrix, pivot_column, basis),
+            AntiCycling::Lexicographic => {
+                get_pivot_row_lexicographic(matrix, pivot_column, &reference)
+            }
+        };
+
+        let (_, bound_hit) = bounded_step(matrix, basis, upper, pivot_column, pivot_row);
+        let leaving = match bound_hit {
+            Some(BoundHit::Entering) => None,
+            Some(BoundHit::Leaving(row)) => Some(row),
+            None => pivot_row,
+        };
+        tracer.record(
+            matrix,
+            basis,
+            &upper.complemented,
+            Some(pivot_column),
+            leaving,
+            || primal_ratios(matrix, pivot_column, basis, &upper.bounds),
+        );
+
+        let pivot_row = match bound_hit {
+            Some(BoundHit::Entering) => {
+                let u = upper.bounds[pivot_column].clone().unwrap();
+                complement_column(matrix, pivot_column, u);
+                upper.complemented[pivot_column] = !upper.complemented[pivot_column];
+                *iterations += 1;
+                continue;
+            }
+            Some(BoundHit::Leaving(row)) => {
+                let leaving = basis[row];
+                let u = upper.bounds[leaving].clone().unwrap();
+                complement_basic(&mut matrix[row], leaving, u);
+                upper.complemented[leaving] = !upper.complemented[leaving];
+                row
+            }
+            None => match pivot_row {
+                Some(row) => row,
+                None => return PivotOutcome::Unbounded(pivot_column),
+            },
+        };
+
+        pricer.pivoted(
+            &|j| matrix[pivot_row][j].clone(),
+            pivot_column,
+            basis[pivot_row],
+        );
+        basis[pivot_row] = pivot_column;
+        *matrix = get_new_matrix(matrix.clone(), pivot_column, pivot_row);
+        *iterations += 1;
+    }
+}
+
+/// Longest step `pivot_column` can take in the bounded-variable simplex,
+/// given the row `pivot_row` the ratio test picked, or `None` if nothing
+/// limits it. Comes with the bound that stops it if the entering column hits
+/// its own upper bound or drives a basic variable to its upper bound sooner
+/// than the ratio test allows.
+fn bounded_step<T: Scalar>(
+    matrix: &[Vec<T>],
+    basis: &[usize],
+    upper: &UpperBounds<T>,
+    pivot_column: usize,
+    pivot_row: Option<usize>,
+) -> (Option<T>, Option<BoundHit>) {
+    let mut step =
+        pivot_row.map(|r| matrix[r].last().unwrap().clone() / matrix[r][pivot_column].clone());
+    let mut bound_hit = None;
+    for (i, row) in matrix.iter().enumerate().take(matrix.len() - 1) {
+        if let Some(u) = &upper.bounds[basis[i]]
+            && row[pivot_column].is_negative()
+        {
+            let ratio =
+                (u.clone() - row.last().unwrap().clone()) / (T::zero() - row[pivot_column].clone());
+            if step
+                .as_ref()
+                .is_none_or(|s| (ratio.clone() - s.clone()).is_negative())
+            {
+                step = Some(ratio);
+                bound_hit = Some(BoundHit::Leaving(i));
+            }
+        }
+    }
+    if let Some(u) = &upper.bounds[pivot_column]
+        && step
+            .as_ref()
+            .is_none_or(|s| (u.clone() - s.clone()).is_negative())
     {
-        if *e < matrix.last().unwrap()[pivot_column] {
-            pivot_column = i;
+        step = Some(u.clone());
+        bound_hit = Some(BoundHit::Entering);
+    }
+    (step, bound_hit)
+}
+
+/// The edges of a tableau, read off its columns.
+struct TableauEdges<'a, T> {
+    matrix: &'a [Vec<T>],
+    basis: &'a [usize],
+    upper: &'a UpperBounds<T>,
+}
+
+impl<T: Scalar> Edges<T> for TableauEdges<'_, T> {
+    fn squared_length(&self, j: usize) -> f64 {
+        self.matrix[..self.matrix.len() - 1]
+            .iter()
+            .map(|row| row[j].to_f64().powi(2))
+            .sum()
+    }
+
+    fn step(&self, j: usize) -> Option<T> {
+        let row = get_pivot_row(self.matrix, j);
+        bounded_step(self.matrix, self.basis, self.upper, j, row).0
+    }
+}
+
+/// Dual simplex: pivots a dual feasible tableau, one with no negative reduced
+/// cost, until no right-hand side is negative or `iterations` reaches the
+/// limit.
+///
+/// The leaving row is the one with the most negative right-hand side, and the
+/// entering column the one among its negative entries with the smallest ratio
+/// `d_j / |a_rj|`, which keeps every reduced cost non-negative. Under Bland's
+/// rule, or with [`AntiCycling::Automatic`] once a basis repeats, the leaving
+/// row is the one with the lowest basic variable and ratio ties go to the
+/// lowest column.
+fn dual_optimize<T: Scalar>(
+    matrix: &mut Vec<Vec<T>>,
+    basis: &mut [usize],
+    iterations: &mut usize,
+    pivoting: Pivoting,
+    tracer: &mut Tracer<T>,
+) -> DualOutcome {
+    let mut bland = pivoting.anti_cycling == AntiCycling::Bland;
+    let mut seen: HashSet<Vec<usize>> = HashSet::new();
+
+    loop {
+        if pivoting.anti_cycling != AntiCycling::Bland && !bland {
+            let mut key = basis.to_vec();
+            key.sort_unstable();
+            if !seen.insert(key) {
+                log("Basis repeated, switching to Bland's rule");
+                bland = true;
+            }
+        }
+
+        let rhs = matrix[0].len() - 1;
+        let mut pivot_row: Option<usize> = None;
+        for (i, row) in matrix.iter().enumerate().take(matrix.len() - 1) {
+            if !row[rhs].is_negative() {
+                continue;
+            }
+            let better = match pivot_row {
+                None => true,
+                Some(r) if bland => basis[i] < basis[r],
+                Some(r) => row[rhs] < matrix[r][rhs],
+            };
+            if better {
+                pivot_row = Some(i);
+            }
+        }
+        // No column is ever complemented here.
+        let complemented = vec![false; rhs];
+        let Some(pivot_row) = pivot_row else {
+            tracer.record(matrix, basis, &complemented, None, None, Vec::new);
+            return DualOutcome::Optimal;
+        };
+        if *iterations >= pivoting.max_iterations {
+            tracer.record(matrix, basis, &complemented, None, None, Vec::new);
+            return DualOutcome::IterationLimit;
+        }
+
+        let objective = matrix.last().unwrap();
+        let mut pivot_column: Option<usize> = None;
+        for j in 0..rhs {
+            let a = &matrix[pivot_row][j];
+            if !a.is_negative() {
+                continue;
+            }
+            if let Some(c) = pivot_column {
+                let difference = objective[j].clone() / (T::zero() - a.clone())
+                    - objective[c].clone() / (T::zero() - matrix[pivot_row][c].clone());
+                if !difference.is_negative() {
+                    continue;
+                }
+            }
+            pivot_column = Some(j);
+        }
+        tracer.record(
+            matrix,
+            basis,
+            &complemented,
+            pivot_column,
+            Some(pivot_row),
+            || {
+                (0..rhs)
+                    .map(|j| {
+                        let a = &matrix[pivot_row][j];
+                        a.is_negative()
+                            .then(|| objective[j].clone() / (T::zero() - a.clone()))
+                    })
+                    .collect()
+            },
+        );
+        let Some(pivot_column) = pivot_column else {
+            return DualOutcome::Infeasible(pivot_row);
+        };
+
+        basis[pivot_row] = pivot_column;
+        *matrix = get_new_matrix(matrix.clone(), pivot_column, pivot_row);
+        *iterations += 1;
+    }
+}
+
+/// What limits the step of the entering column in the bounded-variable
+/// simplex, when it is not a basic variable dropping to zero.
+enum BoundHit {
+    /// The entering column reaches its own upper bound.
+    Entering,
+    /// The basic variable of this row reaches its upper bound.
+    Leaving(usize),
+}
+
+/// Substitutes `u - x` for the non-basic column `column` in `rows`.
+fn complement_column<T: Scalar>(rows: &mut [Vec<T>], column: usize, u: T) {
+    for row in rows.iter_mut() {
+        let rhs = row.len() - 1;
+        row[rhs] = row[rhs].clone() - row[column].clone() * u.clone();
+        row[column] = T::zero() - row[column].clone();
+    }
+}
+
+/// Substitutes `u - x` for the basic column `column` of `row`, keeping it as
+/// the row's basic column with a coefficient of one.
+fn complement_basic<T: Scalar>(row: &mut [T], column: usize, u: T) {
+    for (j, e) in row.iter_mut().enumerate() {
+        if j != column {
+            *e = T::zero() - e.clone();
         }
     }
+    let rhs = row.len() - 1;
+    row[rhs] = row[rhs].clone() + u;
+}
+
+/// Replaces the artificial variables left in the basis at zero level after
+/// phase 1 by any structural or slack column with a non-zero entry in their
+/// row. Rows where no such column exists are linearly dependent on the others
+/// and are removed. Returns the artificial columns that were basic in them.
+fn drive_out_artificials<T: Scalar>(
+    matrix: &mut Vec<Vec<T>>,
+    basis: &mut Vec<usize>,
+    first_artificial: usize,
+) -> Vec<usize> {
+    let mut redundant = vec![];
+    let mut i = 0;
+    while i < basis.len() {
+        if basis[i] < first_artificial {
+            i += 1;
+            continue;
+        }
 
-    pivot_column
+        match (0..first_artificial)
+            .find(|&j| matrix[i][j].is_negative() || matrix[i][j].is_positive())
+        {
+            Some(j) => {
+                basis[i] = j;
+                *matrix = get_new_matrix(matrix.clone(), j, i);
+                i += 1;
+            }
+            None => {
+                matrix.remove(i);
+                redundant.push(basis.remove(i));
+            }
+        }
+    }
+    redundant
 }
 
-fn get_pivot_row(matrix: &Vec<Vec<f32>>, pivot_column: usize) -> usize {
-    let mut pivot_row = 0;
+/// Minimum ratio test over the rows with a positive entry in `pivot_column`,
+/// ties going to the lowest row. `None` if no row has a positive entry.
+pub(crate) fn get_pivot_row<T: Scalar>(matrix: &[Vec<T>], pivot_column: usize) -> Option<usize> {
+    let mut pivot_row: Option<usize> = None;
 
     for (i, e) in matrix.iter().enumerate().take(matrix.len() - 1) {
-        if e.last().unwrap() / e[pivot_column]
-            < matrix[pivot_row].last().unwrap() / matrix[pivot_row][pivot_column]
+        if !e[pivot_column].is_positive() {
+            continue;
+        }
+        if let Some(r) = pivot_row
+            && e.last().unwrap().clone() / e[pivot_column].clone()
+                >= matrix[r].last().unwrap().clone() / matrix[r][pivot_column].clone()
         {
-            pivot_row = i;
+            continue;
+        }
+        pivot_row = Some(i);
+    }
+    pivot_row
+}
+
+/// Bland's entering rule: the lowest-index column with a negative reduced
+/// cost. `None` if the tableau is optimal.
+fn get_pivot_column_bland<T: Scalar>(matrix: &[Vec<T>]) -> Option<usize> {
+    let objective = matrix.last().unwrap();
+    (0..objective.len() - 1).find(|&j| objective[j].is_negative())
+}
+
+/// Minimum ratio test with ties going to the row whose basic variable has the
+/// lowest index, as Bland's rule requires.
+fn get_pivot_row_bland<T: Scalar>(
+    matrix: &[Vec<T>],
+    pivot_column: usize,
+    basis: &[usize],
+) -> Option<usize> {
+    let mut pivot_row: Option<usize> = None;
+
+    for (i, e) in matrix.iter().enumerate().take(matrix.len() - 1) {
+        if !e[pivot_column].is_positive() {
+            continue;
+        }
+        if let Some(r) = pivot_row {
+            let difference = e.last().unwrap().clone() / e[pivot_column].clone()
+                - matrix[r].last().unwrap().clone() / matrix[r][pivot_column].clone();
+            if difference.is_positive() || (!difference.is_negative() && basis[i] > basis[r]) {
+                continue;
+            }
+        }
+        pivot_row = Some(i);
+    }
+    pivot_row
+}
+
+/// Lexicographic ratio test: among the rows with a positive entry in
+/// `pivot_column`, picks the one whose vector of right-hand side followed by
+/// the `reference` columns, divided by the pivot column entry, is
+/// lexicographically smallest. Since the reference columns start as an
+/// identity, no two rows can tie and the objective strictly increases in the
+/// lexicographic sense, so no basis is ever visited twice.
+fn get_pivot_row_lexicographic<T: Scalar>(
+    matrix: &[Vec<T>],
+    pivot_column: usize,
+    reference: &[usize],
+) -> Option<usize> {
+    let rhs = matrix[0].len() - 1;
+    let mut pivot_row: Option<usize> = None;
+
+    for (i, e) in matrix.iter().enumerate().take(matrix.len() - 1) {
+        if !e[pivot_column].is_positive() {
+            continue;
         }
+        if let Some(r) = pivot_row {
+            let smaller = std::iter::once(rhs)
+                .chain(reference.iter().copied())
+                .map(|j| {
+                    e[j].clone() / e[pivot_column].clone()
+                        - matrix[r][j].clone() / matrix[r][pivot_column].clone()
+                })
+                .find(|difference| difference.is_negative() || difference.is_positive())
+                .is_some_and(|difference| difference.is_negative());
+            if !smaller {
+                continue;
+            }
+        }
+        pivot_row = Some(i);
     }
     pivot_row
 }
 
-fn get_new_matrix(
-    mut matrix: Vec<Vec<f32>>,
+pub(crate) fn get_new_matrix<T: Scalar>(
+    mut matrix: Vec<Vec<T>>,
     pivot_column: usize,
     pivot_row: usize,
-) -> Vec<Vec<f32>> {
-    let pivot_element = matrix[pivot_row][pivot_column];
+) -> Vec<Vec<T>> {
+    let pivot_element = matrix[pivot_row][pivot_column].clone();
 
     for e in matrix[pivot_row].iter_mut() {
-        *e /= pivot_element;
+        *e = e.clone() / pivot_element.clone();
     }
 
     let p_row = matrix[pivot_row].clone();
@@ -196,13 +2523,586 @@ fn get_new_matrix(
             continue;
         }
 
-        let pivot_c = row[pivot_column];
-        for (j, e) in row.iter_mut().enumerate() {
-            web_sys::console::log_1(&format!("e = {} - {}*{}", *e, pivot_c, p_row[j]).into());
-            *e -= pivot_c * p_row[j];
+        let pivot_c = row[pivot_column].clone();
+        for (e, p) in row.iter_mut().zip(p_row.iter()) {
+            *e = e.clone() - pivot_c.clone() * p.clone();
         }
     }
-    web_sys::console::log_1(&format!("pivot_element {}", pivot_element).into());
-    print_matrix(&matrix);
     matrix
 }
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::rational::Rational;
+
+    const ALGORITHMS: [Algorithm; 3] = [Algorithm::Tableau, Algorithm::Revised, Algorithm::Dual];
+
+    /// Constraint rows as coefficients, relation and right-hand side.
+    pub(crate) type Rows<'a> = &'a [(&'a [i64], Relation, i64)];
+
+    /// A problem in `x1`, `x2`, … maximizing `target`, with `rows` given as
+    /// their coefficients, relation and right-hand side.
+    pub(crate) fn problem<T: Scalar>(rows: Rows, target: &[i64]) -> Simplex<T> {
+        let value = |a: i64| T::from_f64(a as f64);
+        let mut simplex = Simplex::default();
+        for j in 0..target.len() {
+            simplex.add_variable(format!("x{}", j + 1), VariableKind::Continuous);
+        }
+        for (coefficients, relation, rhs) in rows {
+            let mut row: Vec<T> = coefficients.iter().map(|&a| value(a)).collect();
+            row.push(value(*rhs));
+            simplex.add_constraint(row, *relation);
+        }
+        let mut target: Vec<T> = target.iter().map(|&c| value(c)).collect();
+        target.push(T::zero());
+        simplex.set_target(target);
+        simplex
+    }
+
+    pub(crate) fn objective<T: Scalar>(result: &SolveResult<T>) -> T {
+        match result {
+            SolveResult::Optimal { objective, .. } => objective.clone(),
+            _ => panic!("not optimal: {:?}", result),
+        }
+    }
+
+    #[test]
+    fn two_phase_handles_greater_equal_and_equal_rows() {
+        // min 2x1 + 3x2 with x1 + x2 ≥ 4 and x1 - x2 = 1, at (5/2, 3/2).
+        let simplex = problem::<Rational>(
+            &[
+                (&[1, 1], Relation::GreaterEqual, 4),
+                (&[1, -1], Relation::Equal, 1),
+            ],
+            &[-2, -3],
+        );
+        let SolveResult::Optimal {
+            objective, values, ..
+        } = simplex.run_simplex()
+        else {
+            panic!("not optimal");
+        };
+        assert_eq!(objective, Rational::new(-19, 2));
+        assert_eq!(
+            values,
+            [
+                ("x1".to_string(), Rational::new(5, 2)),
+                ("x2".to_string(), Rational::new(3, 2)),
+            ]
+        );
+    }
+
+    #[test]
+    fn two_phase_drops_redundant_rows() {
+        let simplex = problem::<Rational>(
+            &[(&[1, 1], Relation::Equal, 2), (&[2, 2], Relation::Equal, 4)],
+            &[1, 2],
+        );
+        assert_eq!(objective(&simplex.run_simplex()), 4.into());
+    }
+
+    #[test]
+    fn infeasible() {
+        let mut simplex = problem::<Rational>(
+            &[
+                (&[1, 1], Relation::LessEqual, 2),
+                (&[1, -1], Relation::GreaterEqual, 3),
+            ],
+            &[1, 1],
+        );
+        for algorithm in ALGORITHMS {
+            for start_method in [StartMethod::TwoPhase, StartMethod::BigM] {
+                simplex.set_algorithm(algorithm);
+                simplex.set_start_method(start_method);
+                let result = simplex.run_simplex();
+                assert!(
+                    matches!(result, SolveResult::Infeasible { .. }),
+                    "{} {}: {:?}",
+                    algorithm,
+                    start_method,
+                    result
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn big_m_ray_of_an_infeasible_problem_keeps_a_big_m_trace() {
+        // x2 is unbounded while the artificial of the first row is basic.
+        let mut simplex = problem::<Rational>(
+            &[
+                (&[1, 0], Relation::GreaterEqual, 1),
+                (&[1, 0], Relation::LessEqual, 0),
+            ],
+            &[0, 1],
+        );
+        simplex.set_start_method(StartMethod::BigM);
+        let (result, trace) = simplex.run_traced();
+        assert!(
+            matches!(result, SolveResult::Infeasible { .. }),
+            "{:?}",
+            result
+        );
+        assert!(trace.iter().all(|iteration| iteration.stage == Stage::BigM));
+    }
+
+    #[test]
+    fn unbounded() {
+        let mut simplex = problem::<Rational>(
+            &[
+                (&[1, -1], Relation::LessEqual, 1),
+                (&[-1, 1], Relation::LessEqual, 2),
+            ],
+            &[1, 1],
+        );
+        for algorithm in ALGORITHMS {
+            simplex.set_algorithm(algorithm);
+            let result = simplex.run_simplex();
+            assert!(
+                matches!(result, SolveResult::Unbounded { .. }),
+                "{}: {:?}",
+                algorithm,
+                result
+            );
+        }
+    }
+
+    #[test]
+    fn iteration_limit() {
+        let mut simplex = problem::<Rational>(
+            &[
+                (&[1, 1], Relation::LessEqual, 4),
+                (&[1, 3], Relation::LessEqual, 6),
+            ],
+            &[3, 2],
+        );
+        simplex.set_max_iterations(0);
+        assert!(matches!(
+            simplex.run_simplex(),
+            SolveResult::IterationLimit { iterations: 0, .. }
+        ));
+    }
+
+    /// Beale's example, which cycles under Dantzig's rule with the first row
+    /// winning every ratio tie.
+    fn beale() -> Simplex<Rational> {
+        problem(
+            &[
+                (&[1, -32, -4, 36], Relation::LessEqual, 0),
+                (&[1, -24, -1, 6], Relation::LessEqual, 0),
+                (&[0, 0, 1, 0], Relation::LessEqual, 1),
+            ],
+            &[3, -80, 2, -24],
+        )
+    }
+
+    #[test]
+    fn beale_terminates_under_every_anti_cycling_rule() {
+        let mut simplex = beale();
+        for algorithm in ALGORITHMS {
+            for anti_cycling in [
+                AntiCycling::Automatic,
+                AntiCycling::Bland,
+                AntiCycling::Lexicographic,
+            ] {
+                for pricing_rule in PricingRule::ALL {
+                    simplex.set_algorithm(algorithm);
+                    simplex.set_anti_cycling(anti_cycling);
+                    simplex.set_pricing_rule(pricing_rule);
+                    let result = simplex.run_simplex();
+                    assert_eq!(
+                        objective(&result),
+                        5.into(),
+                        "{} {} {}",
+                        algorithm,
+                        anti_cycling,
+                        pricing_rule
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn free_non_positive_and_shifted_variables() {
+        // x1 free, x2 ≤ 0 and x3 ≥ -2, maximizing -x1 + x2 - x3.
+        let mut simplex = problem::<Rational>(
+            &[
+                (&[1, 0, 0], Relation::GreaterEqual, -3),
+                (&[1, 1, 1], Relation::LessEqual, 10),
+            ],
+            &[-1, 1, -1],
+        );
+        simplex.set_variable_sign(0, VariableSign::Free);
+        simplex.set_variable_sign(1, VariableSign::NonPositive);
+        simplex.set_variable_sign(2, VariableSign::AtLeast((-2).into()));
+        for algorithm in ALGORITHMS {
+            simplex.set_algorithm(algorithm);
+            let result = simplex.run_simplex();
+            let SolveResult::Optimal {
+                objective, values, ..
+            } = &result
+            else {
+                panic!("{}: {:?}", algorithm, result);
+            };
+            assert_eq!(*objective, 5.into(), "{}", algorithm);
+            let values: Vec<Rational> = values.iter().map(|(_, v)| v.clone()).collect();
+            assert_eq!(
+                values,
+                [(-3).into(), 0.into(), (-2).into()],
+                "{}",
+                algorithm
+            );
+        }
+    }
+
+    #[test]
+    fn bounded_variables() {
+        // 1 ≤ x1 ≤ 3, x2 ≤ 2 free below and x3 ≤ 0.
+        let mut simplex = problem::<Rational>(
+            &[
+                (&[1, 2, 1], Relation::LessEqual, 6),
+                (&[1, -1, 0], Relation::GreaterEqual, -4),
+            ],
+            &[1, 1, 1],
+        );
+        simplex.set_bounds(
+            0,
+            Bounds {
+                lower: Some(1.into()),
+                upper: Some(3.into()),
+            },
+        );
+        simplex.set_bounds(
+            1,
+            Bounds {
+                lower: None,
+                upper: Some(2.into()),
+            },
+        );
+        simplex.set_variable_sign(2, VariableSign::NonPositive);
+        for algorithm in ALGORITHMS {
+            simplex.set_algorithm(algorithm);
+            let result = simplex.run_simplex();
+            let SolveResult::Optimal {
+                objective, values, ..
+            } = &result
+            else {
+                panic!("{}: {:?}", algorithm, result);
+            };
+            assert_eq!(*objective, Rational::new(9, 2), "{}", algorithm);
+            let values: Vec<Rational> = values.iter().map(|(_, v)| v.clone()).collect();
+            assert_eq!(
+                values,
+                [3.into(), Rational::new(3, 2), 0.into()],
+                "{}",
+                algorithm
+            );
+        }
+    }
+
+    #[test]
+    fn crossing_bounds_are_infeasible() {
+        let mut simplex = problem::<Rational>(&[(&[1], Relation::LessEqual, 5)], &[1]);
+        simplex.set_bounds(
+            0,
+            Bounds {
+                lower: Some(3.into()),
+                upper: Some(2.into()),
+            },
+        );
+        assert!(matches!(
+            simplex.run_simplex(),
+            SolveResult::Infeasible { .. }
+        ));
+    }
+
+    #[test]
+    fn dual_simplex_from_the_slack_basis() {
+        // min 3x1 + 2x2 with x1 + x2 ≥ 4 and x1 + 3x2 ≥ 6, at (0, 4). The
+        // slack basis is dual feasible, so no phase 1 is needed.
+        let mut simplex = problem::<Rational>(
+            &[
+                (&[1, 1], Relation::GreaterEqual, 4),
+                (&[1, 3], Relation::GreaterEqual, 6),
+            ],
+            &[3, 2],
+        );
+        simplex.set_objective_sense(ObjectiveSense::Minimize);
+        simplex.set_algorithm(Algorithm::Dual);
+        let SolveResult::Optimal {
+            objective, values, ..
+        } = simplex.run_simplex()
+        else {
+            panic!("not optimal");
+        };
+        assert_eq!(objective, 8.into());
+        let values: Vec<Rational> = values.into_iter().map(|(_, v)| v).collect();
+        assert_eq!(values, [0.into(), 4.into()]);
+    }
+
+    #[test]
+    fn dual_simplex_falls_back_without_a_dual_feasible_start() {
+        let mut simplex = problem::<Rational>(
+            &[
+                (&[1, 1], Relation::LessEqual, 4),
+                (&[1, 3], Relation::LessEqual, 6),
+            ],
+            &[3, 2],
+        );
+        let tableau = objective(&simplex.run_simplex());
+        simplex.set_algorithm(Algorithm::Dual);
+        assert_eq!(objective(&simplex.run_simplex()), tableau);
+    }
+
+    #[test]
+    fn strong_duality() {
+        let problems = [
+            problem::<Rational>(
+                &[
+                    (&[1, 1], Relation::LessEqual, 4),
+                    (&[1, 3], Relation::LessEqual, 6),
+                ],
+                &[3, 2],
+            ),
+            problem(
+                &[
+                    (&[1, 1], Relation::GreaterEqual, 4),
+                    (&[1, -1], Relation::Equal, 1),
+                    (&[1, 1], Relation::LessEqual, 10),
+                ],
+                &[-2, -3],
+            ),
+            problem(
+                &[
+                    (&[2, 1, -1], Relation::LessEqual, 8),
+                    (&[1, -2, 3], Relation::GreaterEqual, 2),
+                    (&[1, 1, 1], Relation::Equal, 5),
+                ],
+                &[1, 2, -1],
+            ),
+        ];
+        for mut primal in problems {
+            for objective_sense in [ObjectiveSense::Maximize, ObjectiveSense::Minimize] {
+                primal.set_objective_sense(objective_sense);
+                let dual = primal.dual();
+                let (result, dual_result) = (primal.run_simplex(), dual.run_simplex());
+                let SolveResult::Optimal {
+                    objective: dual_objective,
+                    values: dual_values,
+                    ..
+                } = &dual_result
+                else {
+                    panic!("{}\n{:?}", dual, dual_result);
+                };
+                let SolveResult::Optimal {
+                    objective,
+                    sensitivity,
+                    ..
+                } = &result
+                else {
+                    panic!("{}\n{:?}", primal, result);
+                };
+                assert_eq!(objective, dual_objective, "{}\n{}", primal, dual);
+                let dual_values: Vec<Rational> =
+                    dual_values.iter().map(|(_, v)| v.clone()).collect();
+                assert_eq!(
+                    sensitivity.shadow_prices, dual_values,
+                    "{}\n{}",
+                    primal, dual
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn small_coefficients_are_kept() {
+        // max x1 with x1 + 1e-6 x2 ≤ 1 and x2 = 1e6, where x1 is held at zero
+        // only by the small coefficient.
+        let mut simplex = problem::<f32>(&[], &[1, 0]);
+        simplex.add_constraint(vec![1.0, 1e-6, 1.0], Relation::LessEqual);
+        simplex.add_constraint(vec![0.0, 1.0, 1e6], Relation::Equal);
+        for algorithm in ALGORITHMS {
+            simplex.set_algorithm(algorithm);
+            let result = simplex.run_simplex();
+            assert!(
+                objective(&result).abs() < 1e-3,
+                "{}: {:?}",
+                algorithm,
+                result
+            );
+        }
+    }
+
+    #[test]
+    fn tableau_and_revised_pivot_alike() {
+        // x2 ≤ 1 stops x2 long before its row does, so greatest improvement
+        // has to read the bound to score it.
+        let mut simplex = problem::<Rational>(
+            &[
+                (&[1, 1, 1], Relation::LessEqual, 10),
+                (&[2, 1, 0], Relation::LessEqual, 12),
+                (&[0, 1, 3], Relation::LessEqual, 15),
+            ],
+            &[2, 3, 1],
+        );
+        simplex.set_bounds(
+            1,
+            Bounds {
+                lower: Some(0.into()),
+                upper: Some(1.into()),
+            },
+        );
+        for pricing_rule in PricingRule::ALL {
+            simplex.set_pricing_rule(pricing_rule);
+            let steps = |algorithm| {
+                let mut simplex = simplex.clone();
+                simplex.set_algorithm(algorithm);
+                let (result, trace) = simplex.run_traced();
+                let steps: Vec<(Option<String>, Option<String>)> = trace
+                    .into_iter()
+                    .map(|iteration| (iteration.entering, iteration.leaving))
+                    .collect();
+                (objective(&result), steps)
+            };
+            let (tableau, revised) = (steps(Algorithm::Tableau), steps(Algorithm::Revised));
+            assert_eq!(tableau.0, Rational::new(35, 2), "{}", pricing_rule);
+            assert_eq!(tableau, revised, "{}", pricing_rule);
+            if pricing_rule == PricingRule::GreatestImprovement {
+                // 2 · 6 for x1 beats 3 · 1 for x2.
+                assert_eq!(tableau.1[0].0.as_deref(), Some("x1"));
+            }
+        }
+    }
+
+    #[test]
+    fn knapsack_matches_brute_force() {
+        use crate::branch_and_bound::BranchStatus;
+
+        let mut seed: u64 = 23;
+        let mut next = |range: u64| {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (seed >> 33) % range
+        };
+        for _ in 0..30 {
+            let items = 1 + next(6) as usize;
+            let weights: Vec<i64> = (0..items).map(|_| 1 + next(9) as i64).collect();
+            let values: Vec<i64> = (0..items).map(|_| next(10) as i64).collect();
+            let capacity = next(20) as i64;
+            let total = |set: u32, of: &[i64]| -> i64 {
+                (0..items)
+                    .filter(|i| set >> i & 1 == 1)
+                    .map(|i| of[i])
+                    .sum()
+            };
+            let best = (0..1u32 << items)
+                .filter(|&set| total(set, &weights) <= capacity)
+                .map(|set| total(set, &values))
+                .max()
+                .unwrap();
+
+            let rational = |v: &i64| Rational::from(*v);
+            let knapsack = Simplex::knapsack(
+                &weights.iter().map(rational).collect::<Vec<_>>(),
+                &values.iter().map(rational).collect::<Vec<_>>(),
+                Rational::from(capacity),
+            );
+            let result = knapsack.run_branch_and_bound();
+            let context = format!("{:?} {:?} {}: {:?}", weights, values, capacity, result);
+            assert_eq!(result.status, BranchStatus::Optimal, "{}", context);
+            let incumbent = result.incumbent.unwrap();
+            assert_eq!(incumbent.objective, Rational::from(best), "{}", context);
+            assert!(
+                incumbent
+                    .values
+                    .iter()
+                    .all(|(_, v)| *v == Rational::from(0) || *v == Rational::from(1)),
+                "{}",
+                context
+            );
+        }
+    }
+
+    /// Objective of the problem solved over `T`, or how the solve ended
+    /// otherwise.
+    fn outcome<T: Scalar>(
+        rows: Rows,
+        target: &[i64],
+        algorithm: Algorithm,
+        start_method: StartMethod,
+    ) -> Result<f64, &'static str> {
+        let mut simplex = problem::<T>(rows, target);
+        simplex.set_algorithm(algorithm);
+        simplex.set_start_method(start_method);
+        match simplex.run_simplex() {
+            SolveResult::Optimal { objective, .. } => Ok(objective.to_f64()),
+            SolveResult::Infeasible { .. } => Err("infeasible"),
+            SolveResult::Unbounded { .. } => Err("unbounded"),
+            SolveResult::IterationLimit { .. } => Err("iteration limit"),
+        }
+    }
+
+    #[test]
+    fn floats_match_rationals() {
+        let problems: [(Rows, &[i64]); 5] = [
+            (
+                &[
+                    (&[1, 1], Relation::LessEqual, 4),
+                    (&[1, 3], Relation::LessEqual, 6),
+                ],
+                &[3, 2],
+            ),
+            (
+                &[
+                    (&[1, 1], Relation::GreaterEqual, 4),
+                    (&[1, -1], Relation::Equal, 1),
+                ],
+                &[-2, -3],
+            ),
+            (
+                &[
+                    (&[3, 2, 1], Relation::LessEqual, 10),
+                    (&[2, 5, 3], Relation::LessEqual, 15),
+                    (&[1, 1, 1], Relation::GreaterEqual, 2),
+                ],
+                &[2, 3, 4],
+            ),
+            (
+                &[
+                    (&[1, 1], Relation::LessEqual, 2),
+                    (&[1, -1], Relation::GreaterEqual, 3),
+                ],
+                &[1, 1],
+            ),
+            (
+                &[
+                    (&[1, -1], Relation::LessEqual, 1),
+                    (&[-1, 1], Relation::LessEqual, 2),
+                ],
+                &[1, 1],
+            ),
+        ];
+        for (rows, target) in problems {
+            for algorithm in ALGORITHMS {
+                for start_method in [StartMethod::TwoPhase, StartMethod::BigM] {
+                    let exact = outcome::<Rational>(rows, target, algorithm, start_method);
+                    let context = format!("{:?} {} {}", rows, algorithm, start_method);
+                    for (float, tolerance) in [
+                        (outcome::<f32>(rows, target, algorithm, start_method), 1e-4),
+                        (outcome::<f64>(rows, target, algorithm, start_method), 1e-9),
+                    ] {
+                        match (&exact, float) {
+                            (Ok(exact), Ok(float)) => {
+                                assert!((exact - float).abs() < tolerance, "{}", context)
+                            }
+                            (exact, float) => assert_eq!(*exact, float, "{}", context),
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,286 @@
+use crate::scalar::Scalar;
+
+/// A matrix in compressed sparse row (CSR) form: the non-zero entries of
+/// every row in column order, stored one row after the other.
+///
+/// The number of columns is left open, so a column can be added without
+/// touching the rows that do not use it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CsrMatrix<T> {
+    /// Where the entries of each row start, followed by where the last one
+    /// ends.
+    row_starts: Vec<usize>,
+    column_indices: Vec<usize>,
+    values: Vec<T>,
+}
+
+impl<T> Default for CsrMatrix<T> {
+    fn default() -> Self {
+        Self {
+            row_starts: vec![0],
+            column_indices: vec![],
+            values: vec![],
+        }
+    }
+}
+
+impl<T> CsrMatrix<T> {
+    pub fn rows(&self) -> usize {
+        self.row_starts.len() - 1
+    }
+
+    /// Number of entries stored, all of them non-zero.
+    pub fn nnz(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Non-zero entries of row `i` as `(column, value)`, in column order.
+    pub fn row(&self, i: usize) -> impl Iterator<Item = (usize, &T)> + '_ {
+        let range = self.row_starts[i]..self.row_starts[i + 1];
+        self.column_indices[range.clone()]
+            .iter()
+            .copied()
+            .zip(self.values[range].iter())
+    }
+
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+}
+
+impl<T: Scalar> CsrMatrix<T> {
+    /// Appends a row given by its entries in any order, adding up those of
+    /// the same column and leaving out the ones that are exactly zero.
+    pub fn push_row(&mut self, mut entries: Vec<(usize, T)>) {
+        entries.sort_by_key(|(j, _)| *j);
+        let mut merged: Vec<(usize, T)> = Vec::with_capacity(entries.len());
+        for (j, a) in entries {
+            match merged.last_mut() {
+                Some((k, b)) if *k == j => *b = b.clone() + a,
+                _ => merged.push((j, a)),
+            }
+        }
+        for (j, a) in merged {
+            if a != T::zero() {
+                self.column_indices.push(j);
+                self.values.push(a);
+            }
+        }
+        self.row_starts.push(self.values.len());
+    }
+
+    /// Appends a row given by the value of every column.
+    pub fn push_dense_row(&mut self, row: &[T]) {
+        for (j, a) in row.iter().enumerate() {
+            if *a != T::zero() {
+                self.column_indices.push(j);
+                self.values.push(a.clone());
+            }
+        }
+        self.row_starts.push(self.values.len());
+    }
+
+    /// Row `i` with the value of each of the first `columns` columns.
+    pub fn dense_row(&self, i: usize, columns: usize) -> Vec<T> {
+        let mut row = vec![T::zero(); columns];
+        for (j, a) in self.row(i).filter(|(j, _)| *j < columns) {
+            row[j] = a.clone();
+        }
+        row
+    }
+
+    /// `Σ a_ij x_j` over row `i`, leaving out the columns past the end of
+    /// `x`.
+    pub fn dot_row(&self, i: usize, x: &[T]) -> T {
+        self.row(i)
+            .filter(|(j, _)| *j < x.len())
+            .fold(T::zero(), |sum, (j, a)| sum + a.clone() * x[j].clone())
+    }
+
+    /// The first `columns` columns of the matrix in CSC form.
+    pub fn to_csc(&self, columns: usize) -> CscMatrix<T> {
+        let mut column_starts = vec![0; columns + 1];
+        for &j in self.column_indices.iter().filter(|&&j| j < columns) {
+            column_starts[j + 1] += 1;
+        }
+        for j in 0..columns {
+            column_starts[j + 1] += column_starts[j];
+        }
+
+        // Rows are visited in order, so every column comes out sorted.
+        let mut next = column_starts.clone();
+        let mut row_indices = vec![0; column_starts[columns]];
+        let mut values = vec![T::zero(); column_starts[columns]];
+        for i in 0..self.rows() {
+            for (j, a) in self.row(i).filter(|(j, _)| *j < columns) {
+                row_indices[next[j]] = i;
+                values[next[j]] = a.clone();
+                next[j] += 1;
+            }
+        }
+        CscMatrix {
+            rows: self.rows(),
+            column_starts,
+            row_indices,
+            values,
+        }
+    }
+}
+
+/// A matrix in compressed sparse column (CSC) form: the non-zero entries of
+/// every column in row order, stored one column after the other.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CscMatrix<T> {
+    rows: usize,
+    /// Where the entries of each column start, followed by where the last
+    /// one ends.
+    column_starts: Vec<usize>,
+    row_indices: Vec<usize>,
+    values: Vec<T>,
+}
+
+impl<T> CscMatrix<T> {
+    /// A matrix with `rows` rows and no columns yet.
+    pub fn new(rows: usize) -> Self {
+        Self {
+            rows,
+            column_starts: vec![0],
+            row_indices: vec![],
+            values: vec![],
+        }
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn columns(&self) -> usize {
+        self.column_starts.len() - 1
+    }
+
+    /// Number of entries stored, all of them non-zero.
+    pub fn nnz(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Non-zero entries of column `j` as `(row, value)`, in row order.
+    pub fn column(&self, j: usize) -> impl Iterator<Item = (usize, &T)> + '_ {
+        let range = self.column_starts[j]..self.column_starts[j + 1];
+        self.row_indices[range.clone()]
+            .iter()
+            .copied()
+            .zip(self.values[range].iter())
+    }
+
+    /// The same matrix by rows.
+    pub fn to_csr(&self) -> CsrMatrix<T>
+    where
+        T: Clone,
+    {
+        let mut row_starts = vec![0; self.rows + 1];
+        for &i in self.row_indices.iter() {
+            row_starts[i + 1] += 1;
+        }
+        for i in 0..self.rows {
+            row_starts[i + 1] += row_starts[i];
+        }
+
+        // Columns are visited in order, so every row comes out sorted.
+        let mut next = row_starts.clone();
+        let mut column_indices = vec![0; self.nnz()];
+        let mut values = self.values.clone();
+        for (i, j, a) in self.entries() {
+            column_indices[next[i]] = j;
+            values[next[i]] = a.clone();
+            next[i] += 1;
+        }
+        CsrMatrix {
+            row_starts,
+            column_indices,
+            values,
+        }
+    }
+
+    /// Every non-zero entry as `(row, column, value)`, column by column.
+    pub fn entries(&self) -> impl Iterator<Item = (usize, usize, &T)> + '_ {
+        (0..self.columns()).flat_map(move |j| self.column(j).map(move |(i, a)| (i, j, a)))
+    }
+}
+
+impl<T: Scalar> CscMatrix<T> {
+    /// The matrix whose `i`-th row is `rows[i]`, over the first `columns`
+    /// columns.
+    pub fn from_dense_rows(rows: &[Vec<T>], columns: usize) -> Self {
+        let mut matrix = Self::new(rows.len());
+        for j in 0..columns {
+            matrix.push_column(rows.iter().enumerate().map(|(i, row)| (i, row[j].clone())));
+        }
+        matrix
+    }
+
+    /// Appends a column given by its entries in row order, leaving out the
+    /// ones that are exactly zero.
+    pub fn push_column(&mut self, entries: impl IntoIterator<Item = (usize, T)>) {
+        for (i, a) in entries {
+            if a != T::zero() {
+                self.row_indices.push(i);
+                self.values.push(a);
+            }
+        }
+        self.column_starts.push(self.values.len());
+    }
+
+    /// `Σ a_ij y_i` over column `j`.
+    pub fn dot_column(&self, j: usize, y: &[T]) -> T {
+        self.column(j)
+            .fold(T::zero(), |sum, (i, a)| sum + a.clone() * y[i].clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn push_row_merges_duplicate_columns() {
+        let mut matrix = CsrMatrix::default();
+        matrix.push_row(vec![(3, 2.0), (1, 1.0), (3, -2.0), (1, 4.0), (0, 1e-12)]);
+        let row: Vec<(usize, f64)> = matrix.row(0).map(|(j, a)| (j, *a)).collect();
+        // Columns come out sorted, and only the exact zero of column 3 is
+        // dropped.
+        assert_eq!(row, [(0, 1e-12), (1, 5.0)]);
+    }
+
+    #[test]
+    fn tiny_entries_are_kept() {
+        let mut matrix = CsrMatrix::default();
+        matrix.push_dense_row(&[1e-6_f32, 0.0, -1e-7]);
+        assert_eq!(matrix.nnz(), 2);
+        assert_eq!(matrix.to_csc(3).nnz(), 2);
+    }
+
+    #[test]
+    fn csc_and_csr_round_trip() {
+        let rows = vec![
+            vec![0.0, 2.0, 0.0, 1.0],
+            vec![3.0, 0.0, 0.0, 0.0],
+            vec![0.0, 0.0, 0.0, 0.0],
+            vec![4.0, 5.0, 0.0, 6.0],
+        ];
+        let mut csr = CsrMatrix::default();
+        for row in rows.iter() {
+            csr.push_dense_row(row);
+        }
+        let csc = csr.to_csc(4);
+        assert_eq!(csc, CscMatrix::from_dense_rows(&rows, 4));
+        assert_eq!(csc.nnz(), 6);
+        assert_eq!(
+            csc.column(0).map(|(i, a)| (i, *a)).collect::<Vec<_>>(),
+            [(1, 3.0), (3, 4.0)]
+        );
+        assert_eq!(csc.to_csr(), csr);
+        for (i, row) in rows.iter().enumerate() {
+            assert_eq!(csr.dense_row(i, 4), *row);
+        }
+    }
+}
@@ -0,0 +1,440 @@
+use std::fmt;
+
+use crate::{
+    pricing::dantzig,
+    scalar::Scalar,
+    simplex::{Simplex, StandardForm, Tableau, get_new_matrix, get_pivot_row},
+    trace::{Iteration, Pivot, Stage, primal_ratios},
+};
+
+/// Where a [`Stepper`] stands.
+#[derive(Debug, Clone, PartialEq)]
+pub enum StepStatus<T> {
+    /// Some column still improves the objective.
+    Pivoting,
+    Optimal,
+    /// This column improves the objective but no row limits it.
+    Unbounded(String),
+    /// Phase 1 ended with the artificial variables summing to this.
+    Infeasible(T),
+}
+
+impl<T: fmt::Display> fmt::Display for StepStatus<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StepStatus::Pivoting => write!(f, "Some variable can still improve the objective"),
+            StepStatus::Optimal => write!(f, "Optimal"),
+            StepStatus::Unbounded(column) => {
+                write!(f, "Unbounded: {} can grow without limit", column)
+            }
+            StepStatus::Infeasible(infeasibility) => write!(
+                f,
+                "Infeasible: the artificial variables cannot go below {}",
+                infeasibility
+            ),
+        }
+    }
+}
+
+/// Why [`Stepper::apply_pivot`] rejected a pivot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PivotError {
+    /// The tableau is optimal, unbounded or infeasible already.
+    Finished,
+    /// The row or the column is not in the tableau.
+    OutOfRange,
+    /// The reduced cost of the column is not negative, so bringing it into
+    /// the basis does not improve the objective.
+    NotImproving,
+    /// The pivot element is not positive, so the ratio test does not allow
+    /// the row to leave.
+    NotPositive,
+    /// Another row has a smaller ratio, and its basic variable would become
+    /// negative.
+    NotMinimumRatio,
+}
+
+impl fmt::Display for PivotError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PivotError::Finished => write!(f, "no pivot is left to make"),
+            PivotError::OutOfRange => write!(f, "the cell is not in the tableau"),
+            PivotError::NotImproving => {
+                write!(f, "the column has no negative reduced cost")
+            }
+            PivotError::NotPositive => write!(f, "the pivot element is not positive"),
+            PivotError::NotMinimumRatio => {
+                write!(f, "another row has a smaller ratio")
+            }
+        }
+    }
+}
+
+impl std::error::Error for PivotError {}
+
+/// Everything [`Stepper::undo`] restores.
+#[derive(Debug, Clone)]
+struct State<T> {
+    tableau: Tableau<T>,
+    stage: Stage,
+    status: StepStatus<T>,
+}
+
+/// Two-phase simplex method run one pivot at a time, keeping every earlier
+/// tableau so pivots can be undone.
+///
+/// Pivots follow Dantzig's rule, as [`Simplex::run_simplex`] does by
+/// default: the most negative reduced cost enters and the minimum ratio
+/// leaves. Once phase 1 reaches a feasible basis, its artificials are driven
+/// out and phase 2 starts within the same pivot.
+#[derive(Debug)]
+pub struct Stepper<T> {
+    variables: Vec<String>,
+    target: Vec<T>,
+    /// Problem the tableau belongs to, over the columns of `form` if the
+    /// bounds were not standard, with every upper bound as a row.
+    problem: Simplex<T>,
+    form: Option<StandardForm<T>>,
+    state: State<T>,
+    history: Vec<State<T>>,
+}
+
+impl<T: Scalar> Simplex<T> {
+    /// A [`Stepper`] at the initial tableau of the problem, or `None` if the
+    /// bounds of some variable cross.
+    pub fn stepper(&self) -> Option<Stepper<T>> {
+        if let Some(bounded) = self.with_binary_bounds() {
+            return bounded.stepper();
+        }
+        let standard = self
+            .get_bounds()
+            .iter()
+            .all(|b| b.lower.as_ref().is_some_and(|l| l.is_zero()));
+        let (problem, form) = if standard {
+            (self.with_upper_bound_rows(), None)
+        } else {
+            let form = self.standard_form().ok()?;
+            (form.problem.with_upper_bound_rows(), Some(form))
+        };
+
+        let mut tableau = problem.initial_tableau();
+        let stage = if tableau.has_artificials() {
+            tableau.set_phase_one_objective();
+            Stage::PhaseOne
+        } else {
+            problem.set_phase_two_objective(&mut tableau);
+            Stage::PhaseTwo
+        };
+        let mut stepper = Stepper {
+            variables: self.get_variables(),
+            target: self.get_target(),
+            problem,
+            form,
+            state: State {
+                tableau,
+                stage,
+                status: StepStatus::Pivoting,
+            },
+            history: vec![],
+        };
+        stepper.settle();
+        Some(stepper)
+    }
+}
+
+impl<T: Scalar> Stepper<T> {
+    /// The pivot Dantzig's rule makes next, or `None` once the tableau is
+    /// optimal, unbounded or infeasible.
+    pub fn next_pivot(&self) -> Option<Pivot<T>> {
+        if self.state.status != StepStatus::Pivoting {
+            return None;
+        }
+        let matrix = &self.state.tableau.matrix;
+        let column = entering(matrix)?;
+        let row = get_pivot_row(matrix, column)?;
+        Some(Pivot {
+            row,
+            column,
+            element: matrix[row][column].clone(),
+        })
+    }
+
+    /// Whether pivoting on `row` and `column` improves the objective and
+    /// keeps every basic variable non-negative.
+    pub fn check_pivot(&self, row: usize, column: usize) -> Result<(), PivotError> {
+        if self.state.status != StepStatus::Pivoting {
+            return Err(PivotError::Finished);
+        }
+        let Tableau {
+            matrix,
+            basis,
+            columns,
+            ..
+        } = &self.state.tableau;
+        if row >= basis.len() || column >= columns.len() {
+            return Err(PivotError::OutOfRange);
+        }
+        if !matrix.last().unwrap()[column].is_negative() {
+            return Err(PivotError::NotImproving);
+        }
+        if !matrix[row][column].is_positive() {
+            return Err(PivotError::NotPositive);
+        }
+        let ratio = |i: usize| matrix[i].last().unwrap().clone() / matrix[i][column].clone();
+        let smaller = (0..basis.len())
+            .filter(|&i| matrix[i][column].is_positive())
+            .any(|i| (ratio(row) - ratio(i)).is_positive());
+        if smaller {
+            return Err(PivotError::NotMinimumRatio);
+        }
+        Ok(())
+    }
+
+    /// Whether Dantzig's rule could make the pivot on `row` and `column`:
+    /// it is valid and its column has the most negative reduced cost, ties
+    /// included.
+    pub fn follows_dantzig(&self, row: usize, column: usize) -> bool {
+        if self.check_pivot(row, column).is_err() {
+            return false;
+        }
+        let matrix = &self.state.tableau.matrix;
+        let objective = matrix.last().unwrap();
+        entering(matrix)
+            .is_some_and(|best| (objective[column].clone() - objective[best].clone()).is_zero())
+    }
+
+    /// Pivots on `row` and `column` if [`Stepper::check_pivot`] allows it,
+    /// moving on to phase 2 if phase 1 is over.
+    pub fn apply_pivot(&mut self, row: usize, column: usize) -> Result<(), PivotError> {
+        self.check_pivot(row, column)?;
+        self.history.push(self.state.clone());
+        let tableau = &mut self.state.tableau;
+        tableau.matrix = get_new_matrix(std::mem::take(&mut tableau.matrix), column, row);
+        tableau.basis[row] = column;
+        self.settle();
+        Ok(())
+    }
+
+    /// Goes back to the tableau before the last pivot. Returns whether there
+    /// was one.
+    pub fn undo(&mut self) -> bool {
+        match self.history.pop() {
+            Some(state) => {
+                self.state = state;
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn is_optimal(&self) -> bool {
+        self.state.status == StepStatus::Optimal
+    }
+
+    pub fn status(&self) -> &StepStatus<T> {
+        &self.state.status
+    }
+
+    pub fn stage(&self) -> Stage {
+        self.state.stage
+    }
+
+    /// Pivots made so far.
+    pub fn steps(&self) -> usize {
+        self.history.len()
+    }
+
+    /// The current tableau, with the pivot [`Stepper::next_pivot`] makes and
+    /// its ratio test.
+    pub fn iteration(&self) -> Iteration<T> {
+        let Tableau {
+            matrix,
+            basis,
+            columns,
+            ..
+        } = &self.state.tableau;
+        let name = |j: usize| columns[j].clone();
+        let pivot = self.next_pivot();
+        let column = match &self.state.status {
+            StepStatus::Unbounded(_) => entering(matrix),
+            _ => pivot.as_ref().map(|p| p.column),
+        };
+        let ratios = match column {
+            Some(column) => primal_ratios(matrix, column, basis, &vec![None; columns.len()]),
+            None => vec![],
+        };
+        Iteration {
+            stage: self.state.stage,
+            columns: columns.clone(),
+            tableau: matrix.clone(),
+            objective_m: None,
+            basis: basis.iter().map(|&b| name(b)).collect(),
+            complemented: vec![false; columns.len()],
+            upper: vec![None; columns.len()],
+            entering: column.map(name),
+            leaving: pivot.as_ref().map(|p| name(basis[p.row])),
+            pivot,
+            ratios,
+        }
+    }
+
+    /// Value of each variable at the current basic solution.
+    pub fn values(&self) -> Vec<(String, T)> {
+        let Tableau { matrix, basis, .. } = &self.state.tableau;
+        let n = self.problem.get_variables().len();
+        let mut columns = vec![T::zero(); n];
+        for (i, &b) in basis.iter().enumerate() {
+            if b < n {
+                columns[b] = matrix[i].last().unwrap().clone();
+            }
+        }
+        let values = match &self.form {
+            Some(form) => form.point(&columns),
+            None => columns,
+        };
+        self.variables.iter().cloned().zip(values).collect()
+    }
+
+    /// Value of the target at the current basic solution.
+    pub fn objective(&self) -> T {
+        self.values()
+            .iter()
+            .zip(self.target.iter())
+            .fold(T::zero(), |sum, ((_, x), c)| sum + x.clone() * c.clone())
+    }
+
+    /// Updates the status after a pivot, starting phase 2 if phase 1 cannot
+    /// improve any further and found a feasible basis.
+    fn settle(&mut self) {
+        let State {
+            tableau,
+            stage,
+            status,
+        } = &mut self.state;
+        *status = match entering(&tableau.matrix) {
+            Some(column) => match get_pivot_row(&tableau.matrix, column) {
+                Some(_) => StepStatus::Pivoting,
+                None => StepStatus::Unbounded(tableau.columns[column].clone()),
+            },
+            None if *stage == Stage::PhaseOne => {
+                let infeasibility = tableau.infeasibility();
+                if infeasibility.is_positive() {
+                    StepStatus::Infeasible(infeasibility)
+                } else {
+                    tableau.drop_artificials();
+                    self.problem.set_phase_two_objective(tableau);
+                    *stage = Stage::PhaseTwo;
+                    return self.settle();
+                }
+            }
+            None => StepStatus::Optimal,
+        };
+    }
+}
+
+/// Column Dantzig's rule brings into the basis, or `None` if no reduced cost
+/// is negative.
+fn entering<T: Scalar>(matrix: &[Vec<T>]) -> Option<usize> {
+    let objective = matrix.last().unwrap();
+    dantzig(&objective[..objective.len() - 1])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        rational::Rational,
+        simplex::{Relation, SolveResult, tests::problem},
+    };
+
+    /// Needs phase 1 for its `≥` row before phase 2 can start.
+    fn two_phase() -> Simplex<Rational> {
+        problem(
+            &[
+                (&[1, 1], Relation::LessEqual, 4),
+                (&[1, 3], Relation::LessEqual, 6),
+                (&[1, 0], Relation::GreaterEqual, 1),
+            ],
+            &[3, 2],
+        )
+    }
+
+    #[test]
+    fn pivots_and_undo_round_trip() {
+        let simplex = two_phase();
+        let mut stepper = simplex.stepper().unwrap();
+        let mut seen = vec![(stepper.iteration(), stepper.stage())];
+        while let Some(pivot) = stepper.next_pivot() {
+            assert!(stepper.follows_dantzig(pivot.row, pivot.column));
+            stepper.apply_pivot(pivot.row, pivot.column).unwrap();
+            seen.push((stepper.iteration(), stepper.stage()));
+        }
+        assert!(stepper.is_optimal());
+        assert_eq!(stepper.stage(), Stage::PhaseTwo);
+        assert_eq!(stepper.steps(), seen.len() - 1);
+        let SolveResult::Optimal { objective, .. } = simplex.run_simplex() else {
+            panic!("not optimal");
+        };
+        assert_eq!(stepper.objective(), objective);
+
+        while let Some(expected) = seen.pop() {
+            assert_eq!((stepper.iteration(), stepper.stage()), expected);
+            assert_eq!(stepper.undo(), !seen.is_empty());
+        }
+        assert_eq!(stepper.steps(), 0);
+        assert_eq!(stepper.stage(), Stage::PhaseOne);
+    }
+
+    #[test]
+    fn undo_after_the_optimum_resumes_pivoting() {
+        let mut stepper = two_phase().stepper().unwrap();
+        while let Some(pivot) = stepper.next_pivot() {
+            stepper.apply_pivot(pivot.row, pivot.column).unwrap();
+        }
+        let optimum = stepper.values();
+        assert!(stepper.undo());
+        assert_eq!(*stepper.status(), StepStatus::Pivoting);
+        let pivot = stepper.next_pivot().unwrap();
+        stepper.apply_pivot(pivot.row, pivot.column).unwrap();
+        assert!(stepper.is_optimal());
+        assert_eq!(stepper.values(), optimum);
+    }
+
+    #[test]
+    fn check_pivot_names_what_is_wrong() {
+        // Dantzig's rule brings x1 in, and the first row has the smallest
+        // ratio for it.
+        let mut stepper = problem::<Rational>(
+            &[
+                (&[1, 1], Relation::LessEqual, 4),
+                (&[1, 3], Relation::LessEqual, 6),
+                (&[-1, 1], Relation::LessEqual, 2),
+            ],
+            &[3, 2],
+        )
+        .stepper()
+        .unwrap();
+        let initial = stepper.iteration();
+        assert_eq!(initial.entering.as_deref(), Some("x1"));
+
+        assert_eq!(stepper.check_pivot(0, 0), Ok(()));
+        assert!(stepper.follows_dantzig(0, 0));
+        assert_eq!(stepper.check_pivot(2, 0), Err(PivotError::NotPositive));
+        assert_eq!(stepper.check_pivot(1, 0), Err(PivotError::NotMinimumRatio));
+        assert_eq!(stepper.check_pivot(0, 2), Err(PivotError::NotImproving));
+        assert_eq!(stepper.check_pivot(3, 0), Err(PivotError::OutOfRange));
+
+        // x2 improves too and may enter, but not by Dantzig's rule.
+        assert_eq!(stepper.check_pivot(1, 1), Ok(()));
+        assert!(!stepper.follows_dantzig(1, 1));
+
+        assert_eq!(stepper.apply_pivot(1, 0), Err(PivotError::NotMinimumRatio));
+        assert_eq!(stepper.steps(), 0);
+        assert_eq!(stepper.iteration(), initial);
+
+        while let Some(pivot) = stepper.next_pivot() {
+            stepper.apply_pivot(pivot.row, pivot.column).unwrap();
+        }
+        assert_eq!(stepper.check_pivot(0, 0), Err(PivotError::Finished));
+    }
+}
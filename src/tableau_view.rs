@@ -0,0 +1,129 @@
+use yew::{Callback, Html, Properties, classes, function_component, html};
+
+use crate::{
+    big_m::BigM,
+    rational::Rational,
+    trace::{Iteration, Stage},
+};
+
+#[derive(Clone, PartialEq, Properties)]
+pub struct Props {
+    pub iteration: Iteration<Rational>,
+    /// Whether to highlight the pivot column, row and element, and show the
+    /// ratio test.
+    #[prop_or_default]
+    pub show_pivot: bool,
+    /// Whether the basis is optimal, which marks its variables.
+    #[prop_or_default]
+    pub optimal: bool,
+    /// Called with the row and column of a clicked cell of a constraint row,
+    /// which makes those cells clickable.
+    #[prop_or_default]
+    pub on_select: Option<Callback<(usize, usize)>>,
+}
+
+#[function_component(TableauView)]
+pub fn tableau_view(properties: &Props) -> Html {
+    let Props {
+        iteration,
+        show_pivot,
+        optimal,
+        on_select,
+    } = properties.clone();
+    let width = iteration.columns.len();
+    let last = iteration.tableau.len() - 1;
+    let objective = match iteration.stage {
+        Stage::PhaseOne => "W",
+        _ => "Z",
+    };
+
+    // Without a pivot, the column of an unbounded variable is still shown.
+    let entering = iteration
+        .entering
+        .as_ref()
+        .and_then(|entering| iteration.columns.iter().position(|c| c == entering));
+    let (pivot_row, pivot_column) = match (&iteration.pivot, show_pivot) {
+        (Some(pivot), true) => (Some(pivot.row), Some(pivot.column)),
+        (None, true) => (None, entering),
+        (_, false) => (None, None),
+    };
+    // The dual simplex tests columns and the primal one rows.
+    let dual = iteration.stage == Stage::Dual;
+    let show_ratios = show_pivot && !iteration.ratios.is_empty();
+    let ratio = |k: usize| match iteration.ratios.get(k) {
+        Some(Some(ratio)) => ratio.to_string(),
+        _ => "".to_string(),
+    };
+
+    let value = |i: usize, j: usize| match &iteration.objective_m {
+        Some(m) if i == last => {
+            BigM::new(m[j].clone(), iteration.tableau[i][j].clone()).to_string()
+        }
+        _ => iteration.tableau[i][j].to_string(),
+    };
+    let cell = |i: usize, j: usize| {
+        let class = classes!(
+            (Some(j) == pivot_column).then_some("pivot-column"),
+            (Some(i) == pivot_row).then_some("pivot-row"),
+            (Some(j) == pivot_column && Some(i) == pivot_row).then_some("pivot-element"),
+        );
+        let on_select = on_select.clone().filter(|_| i < last && j < width);
+        match on_select {
+            Some(on_select) => {
+                let onclick = Callback::from(move |_| on_select.emit((i, j)));
+                html! { <td class={classes!(class, "clickable")} {onclick}>{value(i, j)}</td> }
+            }
+            None => html! { <td {class}>{value(i, j)}</td> },
+        }
+    };
+    let header = |j: usize| {
+        let column = &iteration.columns[j];
+        if iteration.complemented.get(j).copied().unwrap_or(false) {
+            html! {
+                <th title={format!("Stands for the upper bound of {} minus {}", column, column)}>
+                    {format!("{}'", column)}
+                </th>
+            }
+        } else {
+            html! { <th>{column}</th> }
+        }
+    };
+
+    html! {
+        <table class="tableau">
+            if optimal {
+                <caption>{"Optimal basis"}</caption>
+            }
+            <tr>
+                <th>{"Basis"}</th>
+                { for (0..width).map(header) }
+                <th>{"RHS"}</th>
+                if show_ratios && !dual {
+                    <th>{"Ratio"}</th>
+                }
+            </tr>
+            {
+                for (0..=last).map(|i| html! {
+                    <tr>
+                        if i < last {
+                            <th class={classes!(optimal.then_some("optimal"))}>{&iteration.basis[i]}</th>
+                        } else {
+                            <th>{objective}</th>
+                        }
+                        { for (0..=width).map(|j| cell(i, j)) }
+                        if show_ratios && !dual {
+                            <td>{if i < last { ratio(i) } else { "".to_string() }}</td>
+                        }
+                    </tr>
+                })
+            }
+            if show_ratios && dual {
+                <tr>
+                    <th>{"Ratio"}</th>
+                    { for (0..width).map(|j| html! { <td>{ratio(j)}</td> }) }
+                    <td></td>
+                </tr>
+            }
+        </table>
+    }
+}
@@ -4,6 +4,8 @@ use yew::{Callback, Event, Html, InputEvent, Properties, function_component, htm
 #[derive(Clone, PartialEq, Properties)]
 pub struct Props {
     pub variables: Vec<String>,
+    /// Current text of each coefficient.
+    pub values: Vec<String>,
     pub on_change_target: Callback<(String, usize)>,
 }
 
@@ -19,6 +21,7 @@ fn get_value_from_input_event(e: InputEvent) -> String {
 pub fn variable_input(properties: &Props) -> Html {
     let Props {
         variables,
+        values,
         on_change_target,
     } = properties.clone();
 
@@ -39,7 +42,7 @@ pub fn variable_input(properties: &Props) -> Html {
                     html! {
                         <>
                             <strong>
-                                <input type="text" oninput={oninputs[i].clone()}/>
+                                <input type="text" value={values.get(i).cloned().unwrap_or_default()} oninput={oninputs[i].clone()}/>
                                 {" "}{v}{if i < variables.len() -1 {" + "} else { "" }}
                             </strong>
                         </>
@@ -0,0 +1,454 @@
+use std::fmt;
+
+use crate::{big_m::BigM, scalar::Scalar};
+
+/// Part of the solve an [`Iteration`] belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stage {
+    /// Phase 1 of the two-phase method, driving the artificial variables to
+    /// zero.
+    PhaseOne,
+    /// Optimization of the target, after phase 1 if there was one.
+    PhaseTwo,
+    /// The Big-M method, with the artificial variables penalized in the
+    /// objective row.
+    BigM,
+    /// The dual simplex method.
+    Dual,
+}
+
+impl fmt::Display for Stage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Stage::PhaseOne => write!(f, "Phase 1"),
+            Stage::PhaseTwo => write!(f, "Phase 2"),
+            Stage::BigM => write!(f, "Big-M"),
+            Stage::Dual => write!(f, "Dual simplex"),
+        }
+    }
+}
+
+/// Position and value of a pivot element.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pivot<T> {
+    pub row: usize,
+    pub column: usize,
+    pub element: T,
+}
+
+/// A tableau the solver went through and the step it took from it. The last
+/// iteration of every stage takes none.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Iteration<T> {
+    pub stage: Stage,
+    /// Name of every column but the right-hand side.
+    pub columns: Vec<String>,
+    /// Constraint rows, then the objective row of the maximized target, each
+    /// ending with its right-hand side.
+    pub tableau: Vec<Vec<T>>,
+    /// `M` part of every entry of the objective row, for the Big-M method.
+    pub objective_m: Option<Vec<T>>,
+    /// Basic variable of each constraint row.
+    pub basis: Vec<String>,
+    /// Whether each column is complemented, standing for `u - x` because the
+    /// variable sits at its upper bound `u`.
+    pub complemented: Vec<bool>,
+    /// Upper bound of each column, if any.
+    pub upper: Vec<Option<T>>,
+    pub entering: Option<String>,
+    /// `None` with an entering variable if it reaches its own upper bound
+    /// instead, or if nothing limits it.
+    pub leaving: Option<String>,
+    pub pivot: Option<Pivot<T>>,
+    /// Value of the ratio test for each constraint row, or for each column
+    /// in the dual simplex, `None` where it does not limit the step.
+    pub ratios: Vec<Option<T>>,
+}
+
+impl<T: fmt::Display> fmt::Display for Iteration<T> {
+    /// Summarizes the step on one line.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: ", self.stage)?;
+        match (&self.entering, &self.leaving, &self.pivot) {
+            (Some(entering), Some(leaving), Some(pivot)) => write!(
+                f,
+                "{} enters, {} leaves, pivot {}",
+                entering, leaving, pivot.element
+            ),
+            (Some(entering), _, _) => write!(f, "{} enters, no variable leaves", entering),
+            (None, Some(leaving), _) => write!(f, "{} leaves, no variable can enter", leaving),
+            _ => write!(f, "no improving pivot left"),
+        }
+    }
+}
+
+impl<T: Scalar> Iteration<T> {
+    /// Explains in words why the step was taken, with the reduced costs and
+    /// ratios behind it, or why the stage ends here.
+    pub fn explanation(&self) -> String {
+        let width = self.columns.len();
+        let last = self.tableau.len() - 1;
+        let column = |name: &String| self.columns.iter().position(|c| c == name);
+        let rhs = |i: usize| self.tableau[i][width].clone();
+        // Reduced costs, symbolic in `M` for the Big-M method.
+        let reduced = |j: usize| {
+            let m = self
+                .objective_m
+                .as_ref()
+                .map_or(T::zero(), |m| m[j].clone());
+            BigM::new(m, self.tableau[last][j].clone())
+        };
+        // Columns fixed at zero, like the artificials in phase 2 of the
+        // revised method, cannot enter.
+        let movable = |j: &usize| !self.upper[*j].as_ref().is_some_and(|u| u.is_zero());
+        let improving = (0..width)
+            .filter(movable)
+            .find(|&j| reduced(j).is_negative());
+        // Operand of a quotient, parenthesized if it is a fraction or has
+        // an `M` part.
+        let operand = |value: String| {
+            if value.contains(['/', ' ']) {
+                format!("({})", value)
+            } else {
+                value
+            }
+        };
+        let pivoting = |row: usize, j: usize| {
+            let element = self.tableau[row][j].abs();
+            let divide = if element == T::one() {
+                String::new()
+            } else {
+                format!("divide row {} by {} and ", row + 1, element)
+            };
+            format!(
+                "{}subtract multiples of it from the other rows so {} has a 1 in row {} and 0 elsewhere",
+                divide,
+                self.columns[j],
+                row + 1
+            )
+        };
+
+        if self.stage == Stage::Dual {
+            return match (self.entering.as_ref().and_then(column), &self.pivot) {
+                (Some(j), Some(pivot)) => {
+                    let row = pivot.row;
+                    let most = (0..last).all(|i| rhs(i) >= rhs(row));
+                    let ratio = self.ratios[j].clone().unwrap_or(T::zero());
+                    format!(
+                        "{} leaves because its value {} is {}; {} enters because {}/{} = {} is the smallest ratio over the negative entries of row {}, which keeps every reduced cost non-negative; {}",
+                        self.basis[row],
+                        rhs(row),
+                        if most { "the most negative" } else { "negative" },
+                        self.columns[j],
+                        operand(reduced(j).to_string()),
+                        operand(self.tableau[row][j].abs().to_string()),
+                        ratio,
+                        row + 1,
+                        pivoting(row, j)
+                    )
+                }
+                _ => match self
+                    .leaving
+                    .as_ref()
+                    .and_then(|leaving| self.basis.iter().position(|b| b == leaving))
+                    .or_else(|| (0..last).find(|&i| rhs(i).is_negative()))
+                {
+                    Some(row) if self.leaving.is_some() => format!(
+                        "{} is negative at {} but row {} has no negative entry, so no point satisfies it and the problem is infeasible",
+                        self.basis[row],
+                        rhs(row),
+                        row + 1
+                    ),
+                    Some(row) => format!(
+                        "Stopped at the iteration limit with {} still negative",
+                        self.basis[row]
+                    ),
+                    None => "Every basic variable is non-negative, so the basis is feasible and, with no negative reduced cost, optimal".to_string(),
+                },
+            };
+        }
+
+        let Some(j) = self.entering.as_ref().and_then(column) else {
+            return match (improving, self.stage) {
+                (Some(j), _) => format!(
+                    "Stopped at the iteration limit with {} still able to improve the objective",
+                    self.columns[j]
+                ),
+                (None, Stage::PhaseOne) => {
+                    let infeasibility = T::zero() - rhs(last);
+                    if infeasibility.is_positive() {
+                        format!(
+                            "No reduced cost is negative, so phase 1 is over with the artificial variables still summing to {}: the problem is infeasible",
+                            infeasibility
+                        )
+                    } else {
+                        "No reduced cost is negative and the artificial variables are all zero, so phase 2 starts from this feasible basis".to_string()
+                    }
+                }
+                (None, _) => "No reduced cost is negative, so the basis is optimal".to_string(),
+            };
+        };
+
+        let cost = reduced(j);
+        let most = (0..width).filter(movable).all(|k| reduced(k) >= cost);
+        let entering = format!(
+            "{} enters because its reduced cost {} is {}",
+            self.columns[j],
+            cost,
+            if most {
+                "the most negative"
+            } else {
+                "negative"
+            }
+        );
+        let Some(pivot) = &self.pivot else {
+            return match &self.upper[j] {
+                Some(u) => format!(
+                    "{}; it reaches its upper bound {} before any basic variable reaches a bound, so its column is complemented and the basis stays the same",
+                    entering, u
+                ),
+                None => format!(
+                    "{}; no entry of its column is positive, so no basic variable limits it and the objective grows without limit",
+                    entering
+                ),
+            };
+        };
+
+        let row = pivot.row;
+        let leaving = &self.basis[row];
+        let ratio = self.ratios[row].clone().unwrap_or(T::zero());
+        let element = self.tableau[row][j].clone();
+        let leaves = if element.is_positive() {
+            format!(
+                "{} leaves because {}/{} = {} is the smallest ratio",
+                leaving,
+                operand(rhs(row).to_string()),
+                operand(element.to_string()),
+                ratio
+            )
+        } else {
+            let u = column(leaving)
+                .and_then(|b| self.upper[b].clone())
+                .unwrap_or(T::zero());
+            format!(
+                "{} leaves at its upper bound {} because ({} - {})/{} = {} is the smallest ratio, so row {} is rewritten for {} - {} first",
+                leaving,
+                u,
+                u,
+                rhs(row),
+                operand(element.abs().to_string()),
+                ratio,
+                row + 1,
+                u,
+                leaving
+            )
+        };
+        format!("{}; {}; {}", entering, leaves, pivoting(row, j))
+    }
+}
+
+impl<T: Scalar> Iteration<BigM<T>> {
+    /// The iteration with every entry reduced to its constant part, the `M`
+    /// parts of the objective row kept in [`Iteration::objective_m`].
+    pub(crate) fn split_m(self) -> Iteration<T> {
+        let constant = |v: BigM<T>| v.c;
+        let last = self.tableau.len() - 1;
+        let objective_m = self.tableau[last].iter().map(|v| v.m.clone()).collect();
+        Iteration {
+            stage: self.stage,
+            columns: self.columns,
+            tableau: self
+                .tableau
+                .into_iter()
+                .map(|row| row.into_iter().map(constant).collect())
+                .collect(),
+            objective_m: Some(objective_m),
+            basis: self.basis,
+            complemented: self.complemented,
+            upper: self.upper.into_iter().map(|u| u.map(constant)).collect(),
+            entering: self.entering,
+            leaving: self.leaving,
+            pivot: self.pivot.map(|p| Pivot {
+                row: p.row,
+                column: p.column,
+                element: p.element.c,
+            }),
+            ratios: self.ratios.into_iter().map(|r| r.map(constant)).collect(),
+        }
+    }
+}
+
+/// Collects the iterations of a solve, or nothing if it is off, so an
+/// untraced solve does not copy its tableaux.
+pub(crate) struct Tracer<V> {
+    iterations: Option<Vec<Iteration<V>>>,
+    stage: Stage,
+    columns: Vec<String>,
+    upper: Vec<Option<V>>,
+}
+
+impl<V: Scalar> Tracer<V> {
+    pub(crate) fn new(on: bool) -> Self {
+        Self {
+            iterations: on.then(Vec::new),
+            stage: Stage::PhaseTwo,
+            columns: vec![],
+            upper: vec![],
+        }
+    }
+
+    pub(crate) fn is_on(&self) -> bool {
+        self.iterations.is_some()
+    }
+
+    /// Starts a stage over tableaux with the given columns and their upper
+    /// bounds.
+    pub(crate) fn begin(&mut self, stage: Stage, columns: &[String], upper: &[Option<V>]) {
+        if self.is_on() {
+            self.stage = stage;
+            self.columns = columns.to_vec();
+            self.upper = upper.to_vec();
+        }
+    }
+
+    /// Records `matrix` and the pivot on it, given as the entering column
+    /// and the leaving row. `ratios` is only evaluated when tracing.
+    pub(crate) fn record(
+        &mut self,
+        matrix: &[Vec<V>],
+        basis: &[usize],
+        complemented: &[bool],
+        entering: Option<usize>,
+        leaving: Option<usize>,
+        ratios: impl FnOnce() -> Vec<Option<V>>,
+    ) {
+        let Some(iterations) = &mut self.iterations else {
+            return;
+        };
+        let name = |j: usize| self.columns[j].clone();
+        let pivot = entering.zip(leaving).map(|(column, row)| Pivot {
+            row,
+            column,
+            element: matrix[row][column].clone(),
+        });
+        iterations.push(Iteration {
+            stage: self.stage,
+            columns: self.columns.clone(),
+            tableau: matrix.to_vec(),
+            objective_m: None,
+            basis: basis.iter().map(|&b| name(b)).collect(),
+            complemented: complemented.to_vec(),
+            upper: self.upper.clone(),
+            entering: entering.map(name),
+            leaving: leaving.map(|row| name(basis[row])),
+            pivot,
+            ratios: ratios(),
+        });
+    }
+
+    pub(crate) fn extend(&mut self, more: impl IntoIterator<Item = Iteration<V>>) {
+        if let Some(iterations) = &mut self.iterations {
+            iterations.extend(more);
+        }
+    }
+
+    /// Takes the iterations recorded so far.
+    pub(crate) fn take(&mut self) -> Vec<Iteration<V>> {
+        self.iterations
+            .as_mut()
+            .map(std::mem::take)
+            .unwrap_or_default()
+    }
+}
+
+/// Ratio test of the primal simplex for `column`: `b_i / a_i` for rows with
+/// `a_i > 0`, and `(u - b_i) / -a_i` for rows with `a_i < 0` whose basic
+/// variable has an upper bound `u`.
+pub(crate) fn primal_ratios<V: Scalar>(
+    matrix: &[Vec<V>],
+    column: usize,
+    basis: &[usize],
+    upper: &[Option<V>],
+) -> Vec<Option<V>> {
+    matrix[..matrix.len() - 1]
+        .iter()
+        .zip(basis.iter())
+        .map(|(row, &b)| {
+            let a = row[column].clone();
+            let rhs = row.last().unwrap().clone();
+            if a.is_positive() {
+                Some(rhs / a)
+            } else if a.is_negative() {
+                upper[b].clone().map(|u| (u - rhs) / (V::zero() - a))
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        rational::Rational,
+        simplex::{Bounds, Relation, Simplex, tests::problem},
+    };
+
+    fn explanations(simplex: &Simplex<Rational>) -> Vec<String> {
+        simplex
+            .run_traced()
+            .1
+            .iter()
+            .map(|iteration| iteration.explanation())
+            .collect()
+    }
+
+    #[test]
+    fn entering_leaving_and_optimal() {
+        let simplex = problem(
+            &[
+                (&[2, 1], Relation::LessEqual, 4),
+                (&[1, 3], Relation::LessEqual, 6),
+            ],
+            &[3, 1],
+        );
+        assert_eq!(
+            explanations(&simplex),
+            [
+                "x1 enters because its reduced cost -3 is the most negative; s1 leaves because 4/2 = 2 is the smallest ratio; divide row 1 by 2 and subtract multiples of it from the other rows so x1 has a 1 in row 1 and 0 elsewhere",
+                "No reduced cost is negative, so the basis is optimal",
+            ]
+        );
+    }
+
+    #[test]
+    fn bound_flip() {
+        let mut simplex = problem(&[(&[1, 1], Relation::LessEqual, 10)], &[2, 1]);
+        simplex.set_bounds(
+            0,
+            Bounds {
+                lower: Some(Rational::from(0)),
+                upper: Some(Rational::from(3)),
+            },
+        );
+        assert_eq!(
+            explanations(&simplex)[0],
+            "x1 enters because its reduced cost -2 is the most negative; it reaches its upper bound 3 before any basic variable reaches a bound, so its column is complemented and the basis stays the same"
+        );
+    }
+
+    #[test]
+    fn unbounded() {
+        let simplex = problem(
+            &[
+                (&[1, -1], Relation::LessEqual, 1),
+                (&[-1, 1], Relation::LessEqual, 2),
+            ],
+            &[1, 1],
+        );
+        assert_eq!(
+            explanations(&simplex).last().unwrap(),
+            "x2 enters because its reduced cost -2 is the most negative; no entry of its column is positive, so no basic variable limits it and the objective grows without limit"
+        );
+    }
+}